@@ -0,0 +1,1550 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpListener},
+    thread::{self, JoinHandle},
+};
+
+use serde_json::Value as JsonValue;
+
+use super::*;
+
+/// A request received by the mock server.
+#[derive(Debug)]
+struct ReceivedRequest {
+    method: String,
+    path: String,
+    body: JsonValue,
+}
+
+/// Starts a mock server that answers each request with the next of the given responses.
+///
+/// Returns the endpoint of the server, and a handle that gives the received requests
+/// once all responses have been sent.
+fn mock_server(responses: Vec<Vec<u8>>) -> (String, JoinHandle<Vec<ReceivedRequest>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/api/json/v3/", listener.local_addr().unwrap());
+
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap().to_string();
+            let path = parts.next().unwrap().to_string();
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let body = serde_json::from_slice(&body).unwrap_or(JsonValue::Null);
+
+            stream.write_all(&response).unwrap();
+            requests.push(ReceivedRequest { method, path, body });
+        }
+        requests
+    });
+
+    (endpoint, handle)
+}
+
+/// Creates a raw HTTP response with the given status, extra headers and body.
+fn response(status: u16, headers: &[(&str, &str)], body: &str) -> Vec<u8> {
+    raw_response(status, headers, body.as_bytes())
+}
+
+/// Creates a raw HTTP response with the given status, extra headers and raw body.
+fn raw_response(status: u16, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("\r\n");
+    let mut response = response.into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+/// Builds a client pointing at the given endpoint with dummy credentials.
+fn client(endpoint: &str) -> Client {
+    Client::builder()
+        .endpoint(endpoint.parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn preflight_unreachable() {
+    // Nothing listens on port 1, so the connection is refused.
+    let client = client("http://127.0.0.1:1/api/json/v3/");
+    match client.preflight() {
+        Err(ClientError::Reqwest(e)) => assert!(e.is_connect()),
+        other => panic!("expected a connect error, got {other:?}"),
+    }
+}
+
+#[test]
+fn request_id_in_error() {
+    let (endpoint, server) = mock_server(vec![response(
+        400,
+        &[("X-Request-Id", "abc123")],
+        r#"{"status":"ERROR","message":"Invalid API key."}"#,
+    )]);
+    let client = client(&endpoint);
+
+    match client.test_auth() {
+        Err(ClientError::Porkbun(e)) => {
+            assert_eq!(e.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(e.message(), "Invalid API key.");
+            assert_eq!(e.request_id(), Some("abc123"));
+        }
+        other => panic!("expected an API error, got {other:?}"),
+    }
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/api/json/v3/ping/");
+    assert_eq!(requests[0].body["apikey"], "apikey");
+}
+
+#[test]
+fn replace_records_by_name_type() {
+    let ok = r#"{"status":"SUCCESS"}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","records":[
+                {"id":"1","name":"www.example.com","type":"A","content":"1.1.1.1","ttl":"600","prio":null,"notes":null},
+                {"id":"2","name":"www.example.com","type":"A","content":"2.2.2.2","ttl":"600","prio":null,"notes":null}
+            ]}"#,
+        ),
+        response(200, &[], r#"{"status":"SUCCESS","id":3}"#),
+        response(200, &[], r#"{"status":"SUCCESS","id":4}"#),
+        response(200, &[], ok),
+    ]);
+    let client = client(&endpoint);
+
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+    let contents: Vec<Content> = ["2.2.2.2", "3.3.3.3", "4.4.4.4"]
+        .iter()
+        .map(|ip| Content::from(&Type::A, ip).unwrap())
+        .collect();
+    let ids = client
+        .replace_records_by_name_type(&domain, &Type::A, &contents, None)
+        .unwrap();
+    assert_eq!(ids, [2, 3, 4]);
+
+    let requests = server.join().unwrap();
+    let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/api/json/v3/dns/retrieveByNameType/example.com/A/www/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/delete/example.com/1/",
+        ]
+    );
+    assert_eq!(requests[1].body["content"], "3.3.3.3");
+    assert_eq!(requests[1].body["name"], "www");
+    assert_eq!(requests[2].body["content"], "4.4.4.4");
+
+    let mismatched = [Content::Txt("hello".to_string())];
+    assert!(matches!(
+        client.replace_records_by_name_type(&domain, &Type::A, &mismatched, None),
+        Err(ClientError::Validation(_))
+    ));
+}
+
+#[test]
+fn replace_records_clamped_ttl() {
+    let (endpoint, server) = mock_server(vec![response(
+        200,
+        &[],
+        r#"{"status":"SUCCESS","records":[
+            {"id":"2","name":"www.example.com","type":"A","content":"2.2.2.2","ttl":"600","prio":null,"notes":null}
+        ]}"#,
+    )]);
+    let client = client(&endpoint);
+
+    // Porkbun clamps the TTL to 600, so the existing record already matches.
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+    let contents = [Content::from(&Type::A, "2.2.2.2").unwrap()];
+    let ids = client
+        .replace_records_by_name_type(&domain, &Type::A, &contents, Some(60))
+        .unwrap();
+    assert_eq!(ids, [2]);
+    assert_eq!(server.join().unwrap().len(), 1);
+}
+
+#[test]
+fn endpoint_trailing_slash() {
+    let with_slash = client("https://api.porkbun.com/api/json/v3/");
+    let without_slash = client("https://api.porkbun.com/api/json/v3");
+    assert_eq!(with_slash.endpoint, without_slash.endpoint);
+    assert_eq!(
+        without_slash
+            .build_url(&["dns", "retrieve", "example.com"])
+            .unwrap()
+            .as_str(),
+        "https://api.porkbun.com/api/json/v3/dns/retrieve/example.com/"
+    );
+    assert_eq!(
+        with_slash.build_url(&["ping"]).unwrap(),
+        without_slash.build_url(&["ping"]).unwrap()
+    );
+}
+
+#[test]
+fn local_address() {
+    let (endpoint, server) = mock_server(vec![response(
+        200,
+        &[],
+        r#"{"status":"SUCCESS","yourIp":"127.0.0.1"}"#,
+    )]);
+    let client = Client::builder()
+        .endpoint(endpoint.parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .local_address(Ipv4Addr::LOCALHOST.into())
+        .build()
+        .unwrap();
+
+    assert_eq!(client.test_auth().unwrap(), Ipv4Addr::LOCALHOST);
+    server.join().unwrap();
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn request_metrics() {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    let (endpoint, server) = mock_server(vec![
+        response(
+            429,
+            &[("Retry-After", "0")],
+            r#"{"status":"ERROR","message":"Too many requests."}"#,
+        ),
+        response(200, &[], r#"{"status":"SUCCESS","yourIp":"127.0.0.1"}"#),
+        response(
+            400,
+            &[],
+            r#"{"status":"ERROR","message":"Invalid domain."}"#,
+        ),
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+    ]);
+    let client = Client::builder()
+        .endpoint(endpoint.parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .max_retries(1)
+        .build()
+        .unwrap();
+    let domain: Box<Domain> = "example.com".parse().unwrap();
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        client.test_auth().unwrap();
+        client.retrieve_dns(domain.root(), Some(1)).unwrap_err();
+        client.test_auth().unwrap_err();
+    });
+    server.join().unwrap();
+
+    let mut counters: Vec<(String, u64)> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let DebugValue::Counter(value) = value else {
+                panic!("expected a counter");
+            };
+            let labels = key
+                .key()
+                .labels()
+                .map(|l| format!("{}={}", l.key(), l.value()))
+                .collect::<Vec<_>>()
+                .join(",");
+            (format!("{}{{{labels}}}", key.key().name()), value)
+        })
+        .collect();
+    counters.sort();
+    assert_eq!(
+        counters,
+        [
+            (
+                "hamsando_errors_total{endpoint=dns/retrieve,kind=api}".to_string(),
+                1
+            ),
+            (
+                "hamsando_errors_total{endpoint=ping,kind=json}".to_string(),
+                1
+            ),
+            (
+                "hamsando_requests_total{endpoint=dns/retrieve,outcome=error}".to_string(),
+                1
+            ),
+            (
+                "hamsando_requests_total{endpoint=ping,outcome=error}".to_string(),
+                1
+            ),
+            (
+                "hamsando_requests_total{endpoint=ping,outcome=success}".to_string(),
+                1
+            ),
+            ("hamsando_retries_total{endpoint=ping}".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn create_dns_record_clamped_ttl() {
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], r#"{"status":"SUCCESS","id":5}"#),
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","records":[
+                {"id":"5","name":"www.example.com","type":"A","content":"1.2.3.4","ttl":"600","prio":null,"notes":null}
+            ]}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+    let content = Content::from(&Type::A, "1.2.3.4").unwrap();
+    let created = client
+        .create_dns_record(&domain, &content, Some(60))
+        .unwrap();
+    assert_eq!(created, Created { id: 5, ttl: 600 });
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].body["ttl"], 60);
+    assert_eq!(requests[1].path, "/api/json/v3/dns/retrieve/example.com/5/");
+}
+
+#[test]
+fn uses_porkbun_dns() {
+    let (endpoint, server) = mock_server(vec![
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","ns":["curitiba.ns.porkbun.com","Fortaleza.ns.porkbun.com","maceio.ns.porkbun.com","salvador.ns.porkbun.com"]}"#,
+        ),
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","ns":["ns1.example.net","curitiba.ns.porkbun.com"]}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+
+    let root: Box<Root> = "example.com".parse().unwrap();
+    assert!(client.uses_porkbun_dns(&root).unwrap());
+    assert!(!client.uses_porkbun_dns(&root).unwrap());
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].path, "/api/json/v3/domain/getNs/example.com/");
+}
+
+#[test]
+fn error_body_handling() {
+    let (endpoint, server) = mock_server(vec![
+        response(500, &[], &"a".repeat(20_000)),
+        raw_response(500, &[], b"\xff\xfeoops"),
+    ]);
+    let client = client(&endpoint);
+
+    let Err(ClientError::Porkbun(e)) = client.test_auth() else {
+        panic!("expected an API error");
+    };
+    assert!(e.message().contains("truncated to 8192 bytes"));
+    assert!(e.message().len() < 9000);
+
+    let Err(ClientError::Porkbun(e)) = client.test_auth() else {
+        panic!("expected an API error");
+    };
+    assert!(
+        e.message()
+            .starts_with("unable to get error message from \"\u{fffd}\u{fffd}oops\"")
+    );
+
+    server.join().unwrap();
+}
+
+#[test]
+fn retrieve_dns_by_subdomain() {
+    let records = r#"{"status":"SUCCESS","records":[
+        {"id":"1","name":"example.com","type":"A","content":"1.1.1.1","ttl":"600","prio":null,"notes":null},
+        {"id":"2","name":"www.example.com","type":"A","content":"2.2.2.2","ttl":"600","prio":null,"notes":null},
+        {"id":"3","name":"www.example.com","type":"TXT","content":"hello","ttl":"600","prio":null,"notes":null},
+        {"id":"4","name":"api.www.example.com","type":"A","content":"3.3.3.3","ttl":"600","prio":null,"notes":null}
+    ]}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], records),
+        response(200, &[], records),
+    ]);
+    let client = client(&endpoint);
+
+    let www: Box<Domain> = "WWW.example.com".parse().unwrap();
+    let ids: Vec<i64> = client
+        .retrieve_dns_by_subdomain(&www)
+        .unwrap()
+        .iter()
+        .map(|r| r.id)
+        .collect();
+    assert_eq!(ids, [2, 3]);
+
+    let apex: Box<Domain> = "example.com".parse().unwrap();
+    let ids: Vec<i64> = client
+        .retrieve_dns_by_subdomain(&apex)
+        .unwrap()
+        .iter()
+        .map(|r| r.id)
+        .collect();
+    assert_eq!(ids, [1]);
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].path, "/api/json/v3/dns/retrieve/example.com/");
+}
+
+#[test]
+fn mx_round_trip() {
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], r#"{"status":"SUCCESS","id":7}"#),
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","records":[
+                {"id":"7","name":"example.com","type":"MX","content":"mail.example.com","ttl":"600","prio":"10","notes":null}
+            ]}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+
+    let domain: Box<Domain> = "example.com".parse().unwrap();
+    let content: Content = "MX:10 mail.example.com".parse().unwrap();
+    let id = client.create_dns(&domain, &content, None).unwrap();
+    let records = client.retrieve_dns(domain.root(), Some(id)).unwrap();
+    assert_eq!(records[0].content, content);
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].body["type"], "MX");
+    assert_eq!(requests[0].body["content"], "mail.example.com");
+    assert_eq!(requests[0].body["prio"], 10);
+}
+
+#[test]
+fn ipv4_endpoint() {
+    let client = Client::builder()
+        .ipv4_endpoint()
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .build()
+        .unwrap();
+    assert_eq!(
+        client.endpoint.as_str(),
+        "https://api-ipv4.porkbun.com/api/json/v3/"
+    );
+}
+
+#[test]
+fn public_ip_cached() {
+    let (endpoint, server) = mock_server(vec![response(
+        200,
+        &[],
+        r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#,
+    )]);
+    let client = client(&endpoint);
+
+    let max_age = Duration::from_secs(60);
+    let ip = client.public_ip_cached(max_age).unwrap();
+    assert_eq!(ip, Ipv4Addr::new(192, 0, 2, 1));
+    server.join().unwrap();
+
+    // The mock server is gone, so this would fail if it made a request.
+    assert_eq!(client.public_ip_cached(max_age).unwrap(), ip);
+    assert!(client.public_ip_cached(Duration::ZERO).is_err());
+}
+
+#[test]
+fn apex_cname() {
+    // No requests are expected, so nothing is listening at the endpoint.
+    let client = client("http://127.0.0.1:1/api/json/v3/");
+    let content = Content::Cname("target.example.com".to_string());
+
+    let apex: Box<Domain> = "example.com".parse().unwrap();
+    let err = client.create_dns(&apex, &content, None).unwrap_err();
+    assert!(matches!(&err, ClientError::Validation(msg) if msg.contains("ALIAS")));
+    let err = client
+        .edit_dns_by_name_type(&apex, &content, None)
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Validation(_)));
+
+    let subdomain: Box<Domain> = "www.example.com".parse().unwrap();
+    let err = client.create_dns(&subdomain, &content, None).unwrap_err();
+    assert!(matches!(err, ClientError::Reqwest(_)));
+}
+
+/// A resolver that only resolves to the expected content after a number of polls.
+#[cfg(feature = "resolve")]
+struct StubResolver {
+    ip: Ipv4Addr,
+    polls_until_resolved: usize,
+    polls: std::cell::Cell<usize>,
+}
+
+#[cfg(feature = "resolve")]
+impl crate::resolve::BlockingResolve for StubResolver {
+    fn resolve(&self, _domain: &Domain, type_: Type) -> Result<Vec<Content>, ClientError> {
+        assert_eq!(type_, Type::A);
+        self.polls.set(self.polls.get() + 1);
+        if self.polls.get() < self.polls_until_resolved {
+            return Ok(vec![Content::A(Ipv4Addr::new(192, 0, 2, 1))]);
+        }
+        Ok(vec![
+            Content::A(Ipv4Addr::new(192, 0, 2, 1)),
+            Content::A(self.ip),
+        ])
+    }
+}
+
+#[cfg(feature = "resolve")]
+#[test]
+fn wait_until_resolves() {
+    let client = client("http://127.0.0.1:1/api/json/v3/");
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+    let ip = Ipv4Addr::new(192, 0, 2, 2);
+    let expected = Content::A(ip);
+
+    let resolver = StubResolver {
+        ip,
+        polls_until_resolved: 3,
+        polls: Default::default(),
+    };
+    let resolved = client
+        .wait_until_resolves_with(&resolver, &domain, &expected, Duration::from_secs(10))
+        .unwrap();
+    assert!(resolved);
+    assert_eq!(resolver.polls.get(), 3);
+
+    let resolver = StubResolver {
+        ip,
+        polls_until_resolved: usize::MAX,
+        polls: Default::default(),
+    };
+    let resolved = client
+        .wait_until_resolves_with(&resolver, &domain, &expected, Duration::from_millis(250))
+        .unwrap();
+    assert!(!resolved);
+    assert!(resolver.polls.get() > 1);
+
+    let alias = Content::Alias("target.example.net".to_string());
+    let err = client
+        .wait_until_resolves(&domain, &alias, Duration::ZERO)
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Validation(_)));
+}
+
+#[test]
+fn retrieve_all_records() {
+    let (endpoint, server) = mock_server(vec![
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","domains":[
+                {"domain":"example.com","status":"ACTIVE","tld":"com"},
+                {"domain":"example.net","status":"ACTIVE","tld":"net"},
+                {"domain":"example.org","status":"ACTIVE","tld":"org"}
+            ]}"#,
+        ),
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","records":[
+                {"id":"1","name":"example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":null,"notes":null},
+                {"id":"2","name":"www.example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":null,"notes":null}
+            ]}"#,
+        ),
+        response(
+            400,
+            &[],
+            r#"{"status":"ERROR","message":"Invalid domain."}"#,
+        ),
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","records":[
+                {"id":"3","name":"example.org","type":"TXT","content":"hello","ttl":"600","prio":null,"notes":null}
+            ]}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+
+    let all = client.retrieve_all_records().unwrap();
+    let ids: Vec<(&str, Vec<i64>)> = all
+        .records
+        .iter()
+        .map(|(root, records)| (root.as_str(), records.iter().map(|r| r.id).collect()))
+        .collect();
+    assert_eq!(ids, [("example.com", vec![1, 2]), ("example.org", vec![3])]);
+
+    assert_eq!(all.failures.len(), 1);
+    let (root, err) = &all.failures[0];
+    assert_eq!(root.as_str(), "example.net");
+    assert!(matches!(err, ClientError::Porkbun(e) if e.message() == "Invalid domain."));
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].path, "/api/json/v3/domain/listAll/");
+    assert_eq!(requests[0].body["start"], "0");
+    assert_eq!(requests[2].path, "/api/json/v3/dns/retrieve/example.net/");
+}
+
+#[test]
+fn rate_limit_status() {
+    let ping = r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], ping),
+        response(
+            200,
+            &[
+                ("X-RateLimit-Limit", "60"),
+                ("X-RateLimit-Remaining", "59"),
+                ("X-RateLimit-Reset", "30"),
+            ],
+            ping,
+        ),
+        response(200, &[], ping),
+        response(
+            503,
+            &[("X-RateLimit-Remaining", "0")],
+            r#"{"status":"ERROR","message":"Rate limit exceeded."}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+
+    client.test_auth().unwrap();
+    assert_eq!(client.rate_limit_status(), None);
+
+    client.test_auth().unwrap();
+    let expected = RateLimitStatus {
+        limit: Some(60),
+        remaining: 59,
+        reset: Some(30),
+    };
+    assert_eq!(client.rate_limit_status(), Some(expected));
+
+    // Responses without the headers keep the last known status.
+    client.test_auth().unwrap();
+    assert_eq!(client.rate_limit_status(), Some(expected));
+
+    client.test_auth().unwrap_err();
+    let expected = RateLimitStatus {
+        limit: None,
+        remaining: 0,
+        reset: None,
+    };
+    assert_eq!(client.rate_limit_status(), Some(expected));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn edit_dns_if_changed() {
+    let record = r#"{"status":"SUCCESS","records":[
+        {"id":"5","name":"www.example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":"0","notes":null}
+    ]}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], record),
+        response(200, &[], record),
+        response(200, &[], record),
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+        response(200, &[], r#"{"status":"SUCCESS","records":[]}"#),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "WWW.example.com".parse().unwrap();
+
+    let same = Content::A(Ipv4Addr::new(192, 0, 2, 1));
+    let outcome = client.edit_dns_if_changed(&domain, 5, &same, None);
+    assert_eq!(outcome.unwrap(), UpdateOutcome::Unchanged);
+    // A TTL below the minimum is clamped, so it's the same as the stored one.
+    let outcome = client.edit_dns_if_changed(&domain, 5, &same, Some(300));
+    assert_eq!(outcome.unwrap(), UpdateOutcome::Unchanged);
+
+    let changed = Content::A(Ipv4Addr::new(192, 0, 2, 2));
+    let outcome = client.edit_dns_if_changed(&domain, 5, &changed, None);
+    assert_eq!(outcome.unwrap(), UpdateOutcome::Updated);
+
+    let err = client
+        .edit_dns_if_changed(&domain, 6, &changed, None)
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Validation(_)));
+
+    let requests = server.join().unwrap();
+    let edits: Vec<_> = requests
+        .iter()
+        .filter(|r| r.path.contains("/dns/edit/"))
+        .collect();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].path, "/api/json/v3/dns/edit/example.com/5/");
+    assert_eq!(edits[0].body["content"], "192.0.2.2");
+}
+
+#[test]
+fn edit_dns_record() {
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","records":[
+                {"id":"5","name":"www.example.com","type":"A","content":"192.0.2.2","ttl":"600","prio":"0","notes":""}
+            ]}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+
+    let content = Content::A(Ipv4Addr::new(192, 0, 2, 2));
+    let record = client
+        .edit_dns_record(&domain, 5, &content, Some(60))
+        .unwrap();
+    assert_eq!(record.id, 5);
+    assert_eq!(record.content, content);
+    assert_eq!(record.ttl, MIN_TTL);
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].path, "/api/json/v3/dns/edit/example.com/5/");
+    assert_eq!(requests[0].body["ttl"], 60);
+    assert_eq!(requests[1].path, "/api/json/v3/dns/retrieve/example.com/5/");
+}
+
+#[test]
+fn delete_all_by_name_type() {
+    let (endpoint, server) = mock_server(vec![
+        response(
+            200,
+            &[],
+            r#"{"status":"SUCCESS","records":[
+                {"id":"1","name":"www.example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":null,"notes":null},
+                {"id":"2","name":"www.example.com","type":"A","content":"192.0.2.2","ttl":"600","prio":null,"notes":null},
+                {"id":"3","name":"www.example.com","type":"A","content":"192.0.2.3","ttl":"600","prio":null,"notes":null}
+            ]}"#,
+        ),
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+        response(200, &[], r#"{"status":"SUCCESS","records":[]}"#),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+
+    assert_eq!(
+        client.delete_all_by_name_type(&domain, &Type::A).unwrap(),
+        3
+    );
+    assert_eq!(
+        client.delete_all_by_name_type(&domain, &Type::A).unwrap(),
+        0
+    );
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[1].path,
+        "/api/json/v3/dns/deleteByNameType/example.com/A/www/"
+    );
+    assert_eq!(
+        requests[2].path,
+        "/api/json/v3/dns/retrieveByNameType/example.com/A/www/"
+    );
+}
+
+#[test]
+fn ensure_dns_absent() {
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+        response(
+            400,
+            &[],
+            r#"{"status":"ERROR","message":"Invalid record ID."}"#,
+        ),
+        response(
+            400,
+            &[],
+            r#"{"status":"ERROR","message":"Invalid API key."}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+    let root: Box<Root> = "example.com".parse().unwrap();
+
+    client.ensure_dns_absent(&root, 5).unwrap();
+    client.ensure_dns_absent(&root, 5).unwrap();
+    let err = client.ensure_dns_absent(&root, 5).unwrap_err();
+    assert!(matches!(err, ClientError::Porkbun(e) if !e.is_not_found()));
+
+    let requests = server.join().unwrap();
+    assert!(
+        requests
+            .iter()
+            .all(|r| r.path == "/api/json/v3/dns/delete/example.com/5/")
+    );
+}
+
+#[test]
+fn max_requests() {
+    let ping = r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#;
+    let (endpoint, server) = mock_server(vec![response(200, &[], ping), response(200, &[], ping)]);
+    let client = Client::builder()
+        .endpoint(endpoint.parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .max_requests(2)
+        .build()
+        .unwrap();
+
+    client.test_auth().unwrap();
+    client.test_auth().unwrap();
+    assert_eq!(server.join().unwrap().len(), 2);
+
+    // The mock server is gone, so this would be an HTTP error if it made a request.
+    assert!(matches!(
+        client.test_auth(),
+        Err(ClientError::QuotaExceeded)
+    ));
+}
+
+#[test]
+fn check_dns() {
+    let records = r#"{"status":"SUCCESS","records":[
+        {"id":"1","name":"example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":null,"notes":null},
+        {"id":"2","name":"example.com","type":"TXT","content":"v=spf1 -all","ttl":"3600","prio":null,"notes":null},
+        {"id":"3","name":"example.com","type":"NS","content":"maceio.ns.porkbun.com","ttl":"86400","prio":null,"notes":null},
+        {"id":"4","name":"www.example.com","type":"A","content":"192.0.2.9","ttl":"600","prio":null,"notes":null}
+    ]}"#;
+    let drifted = r#"{"status":"SUCCESS","records":[
+        {"id":"1","name":"example.com","type":"A","content":"192.0.2.2","ttl":"600","prio":null,"notes":null},
+        {"id":"2","name":"example.com","type":"TXT","content":"v=spf1 -all","ttl":"600","prio":null,"notes":null}
+    ]}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], records),
+        response(200, &[], drifted),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "example.com".parse().unwrap();
+
+    let expected = [
+        RecordSpec {
+            name: "example.com".parse().unwrap(),
+            content: Content::A(Ipv4Addr::new(192, 0, 2, 1)),
+            ttl: Some(300),
+        },
+        RecordSpec {
+            name: "example.com".parse().unwrap(),
+            content: Content::Txt("v=spf1 -all".to_string()),
+            ttl: Some(3600),
+        },
+    ];
+
+    let check = client.check_dns(&domain, &expected).unwrap();
+    assert!(check.is_match(), "{check:?}");
+
+    let check = client.check_dns(&domain, &expected).unwrap();
+    assert!(!check.is_match());
+    assert_eq!(check.missing.len(), 1);
+    assert_eq!(check.missing[0].content, expected[0].content);
+    assert_eq!(check.extra.len(), 1);
+    assert_eq!(check.extra[0].id, 1);
+    assert_eq!(check.mismatched.len(), 1);
+    assert_eq!(check.mismatched[0].1.id, 2);
+
+    let other = [RecordSpec {
+        name: "www.example.com".parse().unwrap(),
+        content: Content::A(Ipv4Addr::new(192, 0, 2, 9)),
+        ttl: None,
+    }];
+    let err = client.check_dns(&domain, &other).unwrap_err();
+    assert!(matches!(err, ClientError::Validation(_)));
+
+    assert_eq!(server.join().unwrap().len(), 2);
+}
+
+#[test]
+fn html_error_page() {
+    let html = format!(
+        "<html><head><title>502 Bad Gateway</title></head><body>{}</body></html>",
+        "<p>cloudflare</p>".repeat(100)
+    );
+    let (endpoint, server) = mock_server(vec![
+        response(502, &[("Content-Type", "text/html; charset=UTF-8")], &html),
+        response(
+            400,
+            &[("Content-Type", "application/json")],
+            r#"{"status":"ERROR","message":"Invalid API key."}"#,
+        ),
+    ]);
+    let client = client(&endpoint);
+
+    let Err(ClientError::Porkbun(e)) = client.test_auth() else {
+        panic!("expected an API error");
+    };
+    assert_eq!(e.status(), StatusCode::BAD_GATEWAY);
+    assert_eq!(e.message(), "upstream returned non-JSON (status 502)");
+
+    let Err(ClientError::Porkbun(e)) = client.test_auth() else {
+        panic!("expected an API error");
+    };
+    assert_eq!(e.message(), "Invalid API key.");
+
+    server.join().unwrap();
+}
+
+#[test]
+fn update_a_record() {
+    let existing = r#"{"status":"SUCCESS","records":[
+        {"id":"7","name":"home.example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":null,"notes":null}
+    ]}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], r#"{"status":"SUCCESS","records":[]}"#),
+        response(200, &[], r#"{"status":"SUCCESS","id":7}"#),
+        response(200, &[], existing),
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+        response(200, &[], existing),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "home.example.com".parse().unwrap();
+
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    assert_eq!(
+        client.update_a_record(&domain, ip, None).unwrap(),
+        UpdateOutcome::Updated
+    );
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+    assert_eq!(
+        client.update_a_record(&domain, ip, None).unwrap(),
+        UpdateOutcome::Updated
+    );
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    assert_eq!(
+        client.update_a_record(&domain, ip, None).unwrap(),
+        UpdateOutcome::Unchanged
+    );
+
+    let requests = server.join().unwrap();
+    let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/api/json/v3/dns/retrieveByNameType/example.com/A/home/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/retrieveByNameType/example.com/A/home/",
+            "/api/json/v3/dns/editByNameType/example.com/A/home/",
+            "/api/json/v3/dns/retrieveByNameType/example.com/A/home/",
+        ]
+    );
+    assert_eq!(requests[1].body["content"], "192.0.2.1");
+    assert_eq!(requests[3].body["content"], "192.0.2.2");
+}
+
+#[test]
+fn probe_endpoints() {
+    let delay = Duration::from_millis(100);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let slow: Url = format!("http://{}/api/json/v3/", listener.local_addr().unwrap())
+        .parse()
+        .unwrap();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            line.clear();
+        }
+        thread::sleep(delay);
+        stream.write_all(&response(404, &[], "")).unwrap();
+    });
+    // Nothing listens on port 1, so the connection is refused.
+    let failing: Url = "http://127.0.0.1:1/api/json/v3/".parse().unwrap();
+
+    let results = Client::probe_endpoints(&[slow.clone(), failing.clone()], Duration::from_secs(5));
+    server.join().unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, slow);
+    assert!(*results[0].1.as_ref().unwrap() >= delay);
+    assert_eq!(results[1].0, failing);
+    assert!(matches!(&results[1].1, Err(ClientError::Reqwest(e)) if e.is_connect()));
+}
+
+#[test]
+fn probe_endpoints_local_address() {
+    let (endpoint, server) = mock_server(vec![response(404, &[], "")]);
+    let endpoint: Url = endpoint.parse().unwrap();
+
+    let local = Client::builder().local_address(Ipv4Addr::LOCALHOST.into());
+    let results = local.probe_endpoints(std::slice::from_ref(&endpoint), Duration::from_secs(5));
+    server.join().unwrap();
+    assert!(results[0].1.is_ok());
+
+    // The endpoint listens on IPv4, so it can't be reached from an IPv6 address.
+    let ipv6 = Client::builder().local_address(Ipv6Addr::LOCALHOST.into());
+    let results = ipv6.probe_endpoints(&[endpoint], Duration::from_secs(5));
+    assert!(results[0].1.is_err());
+}
+
+#[test]
+fn set_record_notes() {
+    let record = r#"{"status":"SUCCESS","records":[
+        {"id":"9","name":"example.com","type":"MX","content":"mail.example.com","ttl":"3600","prio":"10","notes":"old"}
+    ]}"#;
+    let ok = r#"{"status":"SUCCESS"}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], record),
+        response(200, &[], ok),
+        response(200, &[], record),
+        response(200, &[], ok),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "example.com".parse().unwrap();
+
+    client
+        .set_record_notes(&domain, 9, Some("primary mail"))
+        .unwrap();
+    client.set_record_notes(&domain, 9, None).unwrap();
+
+    let requests = server.join().unwrap();
+    let edit = &requests[1];
+    assert_eq!(edit.path, "/api/json/v3/dns/edit/example.com/9/");
+    assert_eq!(edit.body["type"], "MX");
+    assert_eq!(edit.body["content"], "mail.example.com");
+    assert_eq!(edit.body["ttl"], 3600);
+    assert_eq!(edit.body["prio"], 10);
+    assert_eq!(edit.body["notes"], "primary mail");
+    assert!(edit.body.get("name").is_none());
+    assert_eq!(requests[3].body["notes"], "");
+    assert_eq!(requests[3].body["content"], "mail.example.com");
+}
+
+#[test]
+fn with_credentials() {
+    // Unlike the mock server, this keeps connections alive, and reports which connection
+    // each request came in on.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/api/json/v3/", listener.local_addr().unwrap());
+    let (sender, receiver) = std::sync::mpsc::channel();
+    thread::spawn(move || {
+        for (connection, stream) in listener.incoming().enumerate() {
+            let mut stream = stream.unwrap();
+            let sender = sender.clone();
+            thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut content_length = 0;
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                        if let Some((name, value)) = line.trim_end().split_once(':')
+                            && name.eq_ignore_ascii_case("content-length")
+                        {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                        line.clear();
+                    }
+                    if line.is_empty() {
+                        return;
+                    }
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).unwrap();
+                    let body: JsonValue = serde_json::from_slice(&body).unwrap();
+
+                    let ping = r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{ping}",
+                        ping.len()
+                    );
+                    stream.write_all(response.as_bytes()).unwrap();
+                    sender.send((connection, body)).unwrap();
+                }
+            });
+        }
+    });
+
+    let first = client(&endpoint);
+    let second = first.with_credentials("apikey2".to_string(), "secretapikey2".to_string());
+    first.test_auth().unwrap();
+    second.test_auth().unwrap();
+
+    let timeout = Duration::from_secs(5);
+    let (first_connection, first_body) = receiver.recv_timeout(timeout).unwrap();
+    let (second_connection, second_body) = receiver.recv_timeout(timeout).unwrap();
+    assert_eq!(first_connection, second_connection);
+    assert_eq!(first_body["apikey"], "apikey");
+    assert_eq!(second_body["apikey"], "apikey2");
+    assert_eq!(second_body["secretapikey"], "secretapikey2");
+}
+
+#[test]
+fn edit_if_version() {
+    let record = r#"{"status":"SUCCESS","records":[
+        {"id":"5","name":"www.example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":"0","notes":null}
+    ]}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], record),
+        response(200, &[], record),
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+
+    let record_with_ttl = |ttl| Record {
+        id: 5,
+        name: domain.to_owned(),
+        content: Content::A(Ipv4Addr::new(192, 0, 2, 1)),
+        ttl,
+        notes: Some("only the notes differ".to_string()),
+    };
+    let current = record_with_ttl(600);
+    let stale = record_with_ttl(3600);
+    assert_ne!(current.version(), stale.version());
+
+    let content = Content::A(Ipv4Addr::new(192, 0, 2, 2));
+    let err = client
+        .edit_if_version(&domain, 5, stale.version(), &content, None)
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Conflict(5)));
+
+    client
+        .edit_if_version(&domain, 5, current.version(), &content, None)
+        .unwrap();
+
+    let requests = server.join().unwrap();
+    let edits: Vec<_> = requests
+        .iter()
+        .filter(|r| r.path.contains("/dns/edit/"))
+        .collect();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].path, "/api/json/v3/dns/edit/example.com/5/");
+    assert_eq!(edits[0].body["content"], "192.0.2.2");
+}
+
+#[test]
+fn upsert_dns() {
+    let record = r#"{"status":"SUCCESS","records":[
+        {"id":"5","name":"www.example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":"0","notes":null}
+    ]}"#;
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], record),
+        response(200, &[], record),
+        response(200, &[], r#"{"status":"SUCCESS"}"#),
+        response(200, &[], r#"{"status":"SUCCESS","records":[]}"#),
+        response(200, &[], r#"{"status":"SUCCESS","id":"6"}"#),
+    ]);
+    let client = client(&endpoint);
+    let domain: Box<Domain> = "www.example.com".parse().unwrap();
+
+    let same = Content::A(Ipv4Addr::new(192, 0, 2, 1));
+    let outcome = client.upsert_dns(&domain, &same, None).unwrap();
+    assert_eq!(
+        outcome,
+        UpsertOutcome {
+            id: 5,
+            action: UpsertAction::Unchanged
+        }
+    );
+
+    let changed = Content::A(Ipv4Addr::new(192, 0, 2, 2));
+    let outcome = client.upsert_dns(&domain, &changed, None).unwrap();
+    assert_eq!(
+        outcome,
+        UpsertOutcome {
+            id: 5,
+            action: UpsertAction::Updated
+        }
+    );
+
+    let outcome = client.upsert_dns(&domain, &changed, None).unwrap();
+    assert_eq!(
+        outcome,
+        UpsertOutcome {
+            id: 6,
+            action: UpsertAction::Created
+        }
+    );
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[2].path, "/api/json/v3/dns/edit/example.com/5/");
+    assert_eq!(requests[2].body["content"], "192.0.2.2");
+    assert_eq!(requests[4].path, "/api/json/v3/dns/create/example.com/");
+    assert_eq!(requests[4].body["name"], "www");
+}
+
+#[test]
+fn latch_auth_failures() {
+    let (endpoint, server) = mock_server(vec![
+        response(
+            403,
+            &[],
+            r#"{"status":"ERROR","message":"API access is not enabled for this domain."}"#,
+        ),
+        response(200, &[], r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#),
+    ]);
+    let client = Client::builder()
+        .endpoint(endpoint.parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .latch_auth_failures(true)
+        .build()
+        .unwrap();
+
+    match client.test_auth() {
+        Err(ClientError::Porkbun(e)) => assert!(e.is_auth_error()),
+        other => panic!("expected an API error, got {other:?}"),
+    }
+    // The mock server would answer this one successfully, so it wasn't sent.
+    assert!(matches!(
+        client.test_auth(),
+        Err(ClientError::InvalidCredentials)
+    ));
+
+    client.reset_auth_state();
+    assert_eq!(
+        client.test_auth().unwrap(),
+        IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))
+    );
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 2);
+}
+
+#[test]
+fn copy_records() {
+    let records = r#"{"status":"SUCCESS","records":[
+        {"id":"1","name":"example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":"0","notes":null},
+        {"id":"2","name":"www.example.com","type":"CNAME","content":"Example.com.","ttl":"3600","prio":"0","notes":null},
+        {"id":"3","name":"example.com","type":"MX","content":"mail.example.com","ttl":"600","prio":"10","notes":null},
+        {"id":"4","name":"blog.example.com","type":"CNAME","content":"hosting.example.net","ttl":"600","prio":"0","notes":null},
+        {"id":"5","name":"example.com","type":"NS","content":"curitiba.ns.porkbun.com","ttl":"86400","prio":null,"notes":null}
+    ]}"#;
+    let created = |id: u8| response(200, &[], &format!(r#"{{"status":"SUCCESS","id":"{id}"}}"#));
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], records),
+        created(11),
+        created(12),
+        created(13),
+        response(
+            400,
+            &[],
+            r#"{"status":"ERROR","message":"Duplicate record."}"#,
+        ),
+        response(200, &[], records),
+        created(21),
+        created(22),
+        created(23),
+        created(24),
+    ]);
+    let client = client(&endpoint);
+    let from: Box<Root> = "example.com".parse().unwrap();
+    let to: Box<Root> = "example.org".parse().unwrap();
+
+    let results = client.copy_records(&from, &to, false).unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap(), &11);
+    assert_eq!(results[2].as_ref().unwrap(), &13);
+    assert!(matches!(results[3], Err(ClientError::Porkbun(_))));
+
+    let results = client.copy_records(&from, &to, true).unwrap();
+    let ids: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(ids, [21, 22, 23, 24]);
+
+    let requests = server.join().unwrap();
+    let creates: Vec<_> = requests
+        .iter()
+        .filter(|r| r.path.contains("/dns/create/"))
+        .collect();
+    assert_eq!(creates.len(), 8);
+    assert!(
+        creates
+            .iter()
+            .all(|r| r.path == "/api/json/v3/dns/create/example.org/")
+    );
+
+    // Without rewriting, only the names are moved.
+    assert_eq!(creates[0].body["name"], JsonValue::Null);
+    assert_eq!(creates[0].body["content"], "192.0.2.1");
+    assert_eq!(creates[1].body["name"], "www");
+    assert_eq!(creates[1].body["content"], "Example.com.");
+    assert_eq!(creates[1].body["ttl"], 3600);
+    assert_eq!(creates[2].body["content"], "mail.example.com");
+    assert_eq!(creates[2].body["prio"], 10);
+
+    // With rewriting, targets under the old domain move along.
+    assert_eq!(creates[5].body["content"], "example.org.");
+    assert_eq!(creates[6].body["content"], "mail.example.org");
+    assert_eq!(creates[6].body["prio"], 10);
+    assert_eq!(creates[7].body["name"], "blog");
+    assert_eq!(creates[7].body["content"], "hosting.example.net");
+}
+
+#[test]
+fn shared_builder() {
+    let builder = crate::ClientBuilder::<Client>::new()
+        .endpoint("http://127.0.0.1:1/api/json/v3".parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .max_requests(5);
+
+    let blocking = builder.clone().build().unwrap();
+    let async_client = builder.build_async().unwrap();
+    assert_eq!(
+        blocking.endpoint().as_str(),
+        "http://127.0.0.1:1/api/json/v3/"
+    );
+    assert_eq!(blocking.endpoint(), async_client.endpoint());
+    assert_eq!(blocking.credentials, async_client.credentials);
+
+    let async_client = crate::Client::builder()
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .ipv4_endpoint()
+        .build()
+        .unwrap();
+    let blocking = crate::Client::builder()
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .ipv4_endpoint()
+        .build_blocking()
+        .unwrap();
+    assert_eq!(blocking.endpoint().as_str(), crate::IPV4_ENDPOINT);
+    assert_eq!(blocking.endpoint(), async_client.endpoint());
+    assert_eq!(blocking.credentials, async_client.credentials);
+
+    assert!(matches!(
+        Client::builder().apikey("apikey".to_string()).build_async(),
+        Err(ClientBuilderError::MissingField(field)) if field == "secretapikey"
+    ));
+}
+
+#[test]
+fn retry_after() {
+    let (endpoint, server) = mock_server(vec![
+        response(
+            429,
+            &[("Retry-After", "2")],
+            r#"{"status":"ERROR","message":"Too many requests."}"#,
+        ),
+        response(200, &[], r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#),
+    ]);
+    let client = Client::builder()
+        .endpoint(endpoint.parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .max_retries(3)
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    assert_eq!(
+        client.test_auth().unwrap(),
+        IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))
+    );
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_secs(2), "waited {elapsed:?}");
+    assert!(elapsed < Duration::from_secs(4), "waited {elapsed:?}");
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 2);
+}
+
+#[test]
+fn retry_delay_headers() {
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use std::time::UNIX_EPOCH;
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(retry_delay(&headers, 0), Duration::from_secs(1));
+    assert_eq!(retry_delay(&headers, 3), Duration::from_secs(8));
+    assert_eq!(retry_delay(&headers, 10), crate::MAX_RETRY_DELAY);
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+    assert_eq!(retry_delay(&headers, 3), Duration::from_secs(120));
+    // A date in the past means retrying right away.
+    headers.insert(
+        RETRY_AFTER,
+        HeaderValue::from_static("Sun, 06 Nov 1994 08:49:37 GMT"),
+    );
+    assert_eq!(retry_delay(&headers, 3), Duration::ZERO);
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+    assert_eq!(retry_delay(&headers, 0), Duration::from_secs(1));
+
+    assert_eq!(
+        crate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+        Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+    );
+    assert_eq!(
+        crate::parse_http_date("Thu, 29 Feb 2024 23:59:59 GMT"),
+        Some(UNIX_EPOCH + Duration::from_secs(1_709_251_199))
+    );
+    assert_eq!(
+        crate::parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"),
+        None
+    );
+    assert_eq!(
+        crate::parse_http_date("Sun, 06 Nov 1994 08:49:37 UTC"),
+        None
+    );
+    assert_eq!(
+        crate::parse_http_date("Sun, 06 Nov 1994 25:49:37 GMT"),
+        None
+    );
+}
+
+#[test]
+fn create_txt_atomic() {
+    let created = |id: u8| response(200, &[], &format!(r#"{{"status":"SUCCESS","id":"{id}"}}"#));
+    let ok = r#"{"status":"SUCCESS"}"#;
+    let (endpoint, server) = mock_server(vec![
+        created(1),
+        created(2),
+        response(
+            400,
+            &[],
+            r#"{"status":"ERROR","message":"Create error: Invalid content."}"#,
+        ),
+        response(200, &[], ok),
+        response(200, &[], ok),
+    ]);
+    let client = client(&endpoint);
+
+    let domain: Box<Domain> = "default._domainkey.example.com".parse().unwrap();
+    let value = format!("v=DKIM1; k=rsa; p={}", "A".repeat(600));
+    assert!(matches!(
+        client.create_txt_atomic(&domain, &value),
+        Err(ClientError::Porkbun(_))
+    ));
+
+    let requests = server.join().unwrap();
+    let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/delete/example.com/1/",
+            "/api/json/v3/dns/delete/example.com/2/",
+        ]
+    );
+    let chunks: Vec<&str> = requests[..3]
+        .iter()
+        .map(|r| r.body["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(chunks.concat(), value);
+    assert_eq!(chunks[0].len(), 255);
+    assert_eq!(requests[0].body["name"], "default._domainkey");
+}
+
+#[test]
+fn set_delegation() {
+    let created = |id: u8| response(200, &[], &format!(r#"{{"status":"SUCCESS","id":"{id}"}}"#));
+    let ok = r#"{"status":"SUCCESS"}"#;
+    let (endpoint, server) = mock_server(vec![
+        created(7),
+        created(8),
+        created(9),
+        response(
+            400,
+            &[],
+            r#"{"status":"ERROR","message":"Create error: Invalid content."}"#,
+        ),
+        response(200, &[], ok),
+    ]);
+    let client = client(&endpoint);
+
+    let domain: Box<Domain> = "sub.example.com".parse().unwrap();
+    let ns1: Box<Domain> = "ns1.example.net.".parse().unwrap();
+    let ns2: Box<Domain> = "ns2.example.net".parse().unwrap();
+    assert_eq!(
+        client.set_delegation(&domain, &[&ns1, &ns2]).unwrap(),
+        [7, 8]
+    );
+    assert!(matches!(
+        client.set_delegation(&domain, &[&ns1, &ns2]),
+        Err(ClientError::Porkbun(_))
+    ));
+
+    let apex: Box<Domain> = "example.com".parse().unwrap();
+    assert!(matches!(
+        client.set_delegation(&apex, &[&ns1]),
+        Err(ClientError::Validation(_))
+    ));
+
+    let requests = server.join().unwrap();
+    let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/create/example.com/",
+            "/api/json/v3/dns/delete/example.com/9/",
+        ]
+    );
+    for (request, nameserver) in requests[..4]
+        .iter()
+        .zip(["ns1.example.net", "ns2.example.net"].iter().cycle())
+    {
+        assert_eq!(request.body["type"], "NS");
+        assert_eq!(request.body["name"], "sub");
+        assert_eq!(request.body["content"], *nameserver);
+        assert!(request.body.get("prio").is_none());
+    }
+}
+
+#[test]
+fn keep_raw_responses() {
+    let (endpoint, server) = mock_server(vec![
+        response(200, &[], r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#),
+        response(200, &[], r#"{"status":"SUCCESS","yourIp":"secretapikey"}"#),
+    ]);
+    let client = Client::builder()
+        .endpoint(endpoint.parse().unwrap())
+        .apikey("apikey".to_string())
+        .secretapikey("secretapikey".to_string())
+        .keep_raw_responses(true)
+        .build()
+        .unwrap();
+    assert_eq!(client.last_raw_response(), None);
+
+    client.test_auth().unwrap();
+    assert_eq!(
+        client.last_raw_response().as_deref(),
+        Some(r#"{"status":"SUCCESS","yourIp":"192.0.2.1"}"#)
+    );
+
+    assert!(matches!(client.test_auth(), Err(ClientError::Json(_))));
+    assert_eq!(
+        client.last_raw_response().as_deref(),
+        Some(r#"{"status":"SUCCESS","yourIp":"[REDACTED]"}"#)
+    );
+
+    server.join().unwrap();
+}
@@ -1,33 +1,392 @@
-use std::net::IpAddr;
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    net::IpAddr,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
+};
 
-use reqwest::StatusCode;
-use serde::Deserialize;
-use serde_json::Value as JsonValue;
+use reqwest::{
+    StatusCode,
+    header::{HeaderMap, RETRY_AFTER},
+};
+use serde::{Deserialize, de::IgnoredAny};
 use url::Url;
 
 use crate::domain::{Domain, Root};
-use crate::record::{self, Content, Record, Type};
-use crate::{ApiError, ClientBuilderError, ClientError, Payload};
+use crate::record::{self, Content, Record, RecordSpec, Type};
+#[cfg(feature = "resolve")]
+use crate::resolve::{self, Resolve};
+use crate::{ApiError, ClientBuilderError, ClientError, Credentials, Payload, stats};
+
+/// Default endpoint of the Porkbun API.
+pub(crate) const DEFAULT_ENDPOINT: &str = "https://api.porkbun.com/api/json/v3/";
+
+/// Endpoint of the Porkbun API that is only reachable over IPv4.
+pub(crate) const IPV4_ENDPOINT: &str = "https://api-ipv4.porkbun.com/api/json/v3/";
+
+/// Minimum TTL that Porkbun applies to records.
+pub(crate) const MIN_TTL: i64 = 600;
+
+/// A newly created DNS record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Created {
+    /// The ID of the record.
+    pub id: i64,
+    /// The effective TTL of the record, after Porkbun's clamping.
+    pub ttl: i64,
+}
+
+/// The result of checking the records of a domain against the desired ones.
+///
+/// Returned by [`Client::check_dns`].
+#[derive(Debug)]
+pub struct DnsCheck<'a> {
+    /// The desired records for which no record with the same content exists.
+    pub missing: Vec<&'a RecordSpec>,
+    /// The records that don't correspond to any of the desired records.
+    pub extra: Vec<Record>,
+    /// The desired records for which a record with the same content, but a different
+    /// TTL, exists.
+    pub mismatched: Vec<(&'a RecordSpec, Record)>,
+}
+
+impl DnsCheck<'_> {
+    /// Returns whether the records exactly match the desired ones.
+    pub fn is_match(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// The outcome of a conditional update of a DNS record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// The record already had the desired value, so no edit was made.
+    Unchanged,
+    /// The record was edited.
+    Updated,
+}
+
+/// The result of [`Client::upsert_dns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpsertOutcome {
+    /// The ID of the record.
+    pub id: i64,
+    /// What was done to the record.
+    pub action: UpsertAction,
+}
+
+/// What [`Client::upsert_dns`] did to make the record match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertAction {
+    /// There was no record, so one was created.
+    Created,
+    /// An existing record was edited.
+    Updated,
+    /// An existing record already had the desired value, so no change was made.
+    Unchanged,
+}
+
+/// The rate limit budget, as reported by Porkbun in the headers of a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// The number of requests allowed in the current window, if reported.
+    pub limit: Option<u64>,
+    /// The number of requests remaining in the current window.
+    pub remaining: u64,
+    /// The value of the `X-RateLimit-Reset` header, if reported.
+    pub reset: Option<u64>,
+}
+
+impl RateLimitStatus {
+    /// Parses the rate limit headers of a response.
+    ///
+    /// Returns `None` if the response doesn't report the remaining budget.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name| headers.get(name)?.to_str().ok()?.trim().parse().ok();
+        Some(Self {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining")?,
+            reset: header("x-ratelimit-reset"),
+        })
+    }
+}
+
+/// The delay before the first retry of a rate limited request, if Porkbun doesn't send a
+/// `Retry-After` header.
+///
+/// This is doubled for every further retry, up to [`MAX_RETRY_DELAY`].
+pub(crate) const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum delay before retrying a rate limited request, if Porkbun doesn't send a
+/// `Retry-After` header.
+pub(crate) const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Names of the months in an HTTP-date.
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Gets the delay before retrying a rate limited request, given the headers of the
+/// response and the number of retries made so far.
+///
+/// The `Retry-After` header is honoured, in both its seconds and its HTTP-date form.
+/// Without one, the delay backs off exponentially.
+pub(crate) fn retry_delay(headers: &HeaderMap, retries: u32) -> Duration {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            let value = value.trim();
+            match value.parse() {
+                Ok(secs) => Some(Duration::from_secs(secs)),
+                Err(_) => parse_http_date(value)
+                    .map(|date| date.duration_since(SystemTime::now()).unwrap_or_default()),
+            }
+        })
+        .unwrap_or_else(|| {
+            INITIAL_RETRY_DELAY
+                .saturating_mul(2u32.saturating_pow(retries))
+                .min(MAX_RETRY_DELAY)
+        })
+}
+
+/// Parses an HTTP-date in the preferred IMF-fixdate format, e.g.
+/// `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub(crate) fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let mut fields = value.split_ascii_whitespace();
+    let (Some(_weekday), Some(day), Some(month), Some(year), Some(time), Some("GMT"), None) = (
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+    ) else {
+        return None;
+    };
+    let day: u64 = day.parse().ok().filter(|d| (1..=31).contains(d))?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u64;
+    let year: u64 = year.parse().ok().filter(|y| *y >= 1970)?;
+    let mut time = time.split(':').map(|t| t.parse::<u64>().ok());
+    let (Some(Some(hours)), Some(Some(minutes)), Some(Some(seconds)), None) =
+        (time.next(), time.next(), time.next(), time.next())
+    else {
+        return None;
+    };
+    if hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    // The number of days since the Unix epoch, counting years from March so that the
+    // leap day comes last.
+    let (year, month) = if month < 2 {
+        (year - 1, month + 10)
+    } else {
+        (year, month - 2)
+    };
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let days = year * 365 + year / 4 - year / 100 + year / 400 + day_of_year - 719_468;
+
+    let secs = days * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// The maximum number of domains returned by a single `domain/listAll` call.
+pub(crate) const LIST_DOMAINS_PAGE_SIZE: usize = 1000;
+
+/// The DNS records of every domain in the account.
+///
+/// Retrieving the records of a domain can fail without the others being affected, so
+/// the failures are collected alongside the records that were retrieved.
+#[derive(Debug, Default)]
+pub struct AccountRecords {
+    /// The records of each domain whose records were retrieved.
+    pub records: BTreeMap<Box<Root>, Vec<Record>>,
+    /// The domains whose records couldn't be retrieved, with the error.
+    pub failures: Vec<(Box<Root>, ClientError)>,
+}
+
+/// Hosts of Porkbun's default nameservers.
+const PORKBUN_NAMESERVERS: [&str; 4] = [
+    "curitiba.ns.porkbun.com",
+    "fortaleza.ns.porkbun.com",
+    "maceio.ns.porkbun.com",
+    "salvador.ns.porkbun.com",
+];
+
+/// Returns whether the given host is one of Porkbun's default nameservers.
+pub(crate) fn is_porkbun_nameserver(host: &str) -> bool {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    PORKBUN_NAMESERVERS
+        .iter()
+        .any(|ns| ns.eq_ignore_ascii_case(host))
+}
+
+/// Compares the records of a domain against the desired ones.
+///
+/// Records are paired with the desired records by content, after which their TTLs are
+/// compared, taking Porkbun's clamping into account.
+pub(crate) fn check_records(mut records: Vec<Record>, expected: &[RecordSpec]) -> DnsCheck<'_> {
+    let mut check = DnsCheck {
+        missing: Vec::new(),
+        extra: Vec::new(),
+        mismatched: Vec::new(),
+    };
+    for spec in expected {
+        match records.iter().position(|r| r.content == spec.content) {
+            Some(i) => {
+                let record = records.swap_remove(i);
+                if spec.ttl.is_some_and(|ttl| ttl.max(MIN_TTL) != record.ttl) {
+                    check.mismatched.push((spec, record));
+                }
+            }
+            None => check.missing.push(spec),
+        }
+    }
+    check.extra = records;
+    check
+}
+
+/// Checks that a record with the given content can be created at the given domain.
+///
+/// DNS doesn't allow a `CNAME` at the zone apex, so Porkbun would reject it without a
+/// helpful message.
+pub(crate) fn validate_record(domain: &Domain, content: &Content) -> Result<(), ClientError> {
+    if matches!(content, Content::Cname(_)) && domain.prefix().is_none() {
+        return Err(ClientError::Validation(format!(
+            "{domain}: a CNAME record can't be created at the zone apex, use an ALIAS record instead"
+        )));
+    }
+    Ok(())
+}
+
+/// Rewrites a hostname at or under `from` to the same name under `to`.
+///
+/// Returns `None` if the hostname isn't `from` or one of its subdomains.
+pub(crate) fn rewrite_target(target: &str, from: &Root, to: &Root) -> Option<String> {
+    let (host, dot) = match target.strip_suffix('.') {
+        Some(host) => (host, "."),
+        None => (target, ""),
+    };
+    let from = from.not_fqdn().as_str();
+    let to = to.not_fqdn().as_str();
+
+    if host.eq_ignore_ascii_case(from) {
+        return Some(format!("{to}{dot}"));
+    }
+    let separator_idx = host.len().checked_sub(from.len() + 1)?;
+    let (prefix, rest) = host.split_at_checked(separator_idx)?;
+    rest.strip_prefix('.')
+        .is_some_and(|rest| rest.eq_ignore_ascii_case(from))
+        .then(|| format!("{prefix}.{to}{dot}"))
+}
+
+/// Gets the name and content that a record of `from` gets when copied to `to`.
+pub(crate) fn copied_record(
+    record: &Record,
+    from: &Root,
+    to: &Root,
+    rewrite_targets: bool,
+) -> Result<(Box<Domain>, Content), ClientError> {
+    let name = match record.name.prefix() {
+        Some(prefix) => format!("{prefix}.{}", to.as_str()),
+        None => to.as_str().to_string(),
+    };
+    let name = Domain::parse(&name).map_err(|e| ClientError::Validation(e.to_string()))?;
+
+    let rewrite = |target: &str| {
+        rewrite_targets
+            .then(|| rewrite_target(target, from, to))
+            .flatten()
+            .unwrap_or_else(|| target.to_string())
+    };
+    let content = match &record.content {
+        Content::Cname(target) => Content::Cname(rewrite(target)),
+        Content::Alias(target) => Content::Alias(rewrite(target)),
+        Content::Mx {
+            priority,
+            target: Some(target),
+        } => Content::Mx {
+            priority: *priority,
+            target: Some(
+                Domain::parse(&rewrite(target.as_str()))
+                    .map_err(|e| ClientError::Validation(e.to_string()))?,
+            ),
+        },
+        content => content.clone(),
+    };
+    Ok((name, content))
+}
 
 /// Builder for a [Client] that handles default values.
-pub struct ClientBuilder {
+///
+/// The same builder configures both the async [Client] and the blocking
+/// [`blocking::Client`](crate::blocking::Client), so their options can't drift apart.
+/// `C` is the client that `build` builds, but either flavor can be built with
+/// [`ClientBuilder::build_async`] and [`ClientBuilder::build_blocking`].
+pub struct ClientBuilder<C = Client> {
     endpoint: Option<Url>,
     apikey: Option<String>,
     secretapikey: Option<String>,
+    pub(crate) local_address: Option<IpAddr>,
+    max_requests: Option<usize>,
+    max_retries: u32,
+    latch_auth_failures: bool,
+    keep_raw_responses: bool,
+    flavor: PhantomData<fn() -> C>,
+}
+
+/// The configuration gathered by a [`ClientBuilder`], with the defaults applied.
+pub(crate) struct ClientConfig {
+    pub(crate) endpoint: Url,
+    pub(crate) apikey: String,
+    pub(crate) secretapikey: String,
+    pub(crate) max_requests: Option<usize>,
+    pub(crate) max_retries: u32,
+    pub(crate) latch_auth_failures: bool,
+    pub(crate) keep_raw_responses: bool,
 }
 
-impl ClientBuilder {
-    fn new() -> Self {
+impl<C> Clone for ClientBuilder<C> {
+    fn clone(&self) -> Self {
+        Self {
+            endpoint: self.endpoint.clone(),
+            apikey: self.apikey.clone(),
+            secretapikey: self.secretapikey.clone(),
+            local_address: self.local_address,
+            max_requests: self.max_requests,
+            max_retries: self.max_retries,
+            latch_auth_failures: self.latch_auth_failures,
+            keep_raw_responses: self.keep_raw_responses,
+            flavor: PhantomData,
+        }
+    }
+}
+
+impl<C> ClientBuilder<C> {
+    pub(crate) fn new() -> Self {
         Self {
             endpoint: None,
             apikey: None,
             secretapikey: None,
+            local_address: None,
+            max_requests: None,
+            max_retries: 0,
+            latch_auth_failures: false,
+            keep_raw_responses: false,
+            flavor: PhantomData,
         }
     }
 
     /// Sets the API endpoint to the one given.
     ///
-    /// The endpoint should have a trailing slash, as per [Url]'s semantics.
+    /// If the path of the endpoint doesn't end in a slash, one is appended, so that the
+    /// last path segment isn't dropped when joining the paths of the API calls onto it.
     pub fn endpoint(mut self, endpoint: Url) -> Self {
         self.endpoint = Some(endpoint);
         self
@@ -35,7 +394,8 @@ impl ClientBuilder {
 
     /// In the case that `endpoint` is the Some variant, sets the API endpoint to it.
     ///
-    /// The endpoint should have a trailing slash, as per [Url]'s semantics.
+    /// If the path of the endpoint doesn't end in a slash, one is appended, so that the
+    /// last path segment isn't dropped when joining the paths of the API calls onto it.
     pub fn endpoint_if_some(mut self, endpoint: Option<Url>) -> Self {
         if let Some(endpoint) = endpoint {
             self.endpoint = Some(endpoint);
@@ -43,6 +403,19 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the API endpoint to Porkbun's IPv4-only endpoint.
+    ///
+    /// This endpoint is only reachable over IPv4, so [`Client::test_auth`] reports the
+    /// IPv4 address of the caller, even on hosts that prefer IPv6.
+    pub fn ipv4_endpoint(mut self) -> Self {
+        self.endpoint = Some(
+            IPV4_ENDPOINT
+                .parse()
+                .expect("the IPv4 endpoint should be a valid URL"),
+        );
+        self
+    }
+
     /// Sets the API key to the one given.
     pub fn apikey(mut self, apikey: String) -> Self {
         self.apikey = Some(apikey);
@@ -55,19 +428,69 @@ impl ClientBuilder {
         self
     }
 
-    /// Builds a [Client] from the builder.
+    /// Sets the local address that requests are sent from.
+    ///
+    /// On a multi-homed host, this determines which interface the requests go out of,
+    /// and therefore which public IP address [`Client::test_auth`] reports.
+    pub fn local_address(mut self, local_address: IpAddr) -> Self {
+        self.local_address = Some(local_address);
+        self
+    }
+
+    /// Limits the total number of API calls that the client will make.
+    ///
+    /// Once the budget is spent, every call returns a `QuotaExceeded` error without
+    /// sending a request. This is a safety rail against e.g. a buggy loop in automation.
+    pub fn max_requests(mut self, max_requests: usize) -> Self {
+        self.max_requests = Some(max_requests);
+        self
+    }
+
+    /// Sets how many times a request that is rate limited (`429`) is retried.
+    ///
+    /// Before retrying, the client waits for as long as the `Retry-After` header of the
+    /// response asks, or with an exponential backoff starting at one second if there is
+    /// none. Every retry counts towards [`ClientBuilder::max_requests`]. Defaults to 0,
+    /// i.e. no retries.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets whether the client stops making API calls once its credentials are rejected.
+    ///
+    /// When enabled, a `401` or `403` response latches the client into a failed state, in
+    /// which every call returns an `InvalidCredentials` error without sending a request,
+    /// as it would fail anyway. [`Client::reset_auth_state`] leaves this state, e.g. after
+    /// the API access of the account has been fixed. Disabled by default.
+    pub fn latch_auth_failures(mut self, latch_auth_failures: bool) -> Self {
+        self.latch_auth_failures = latch_auth_failures;
+        self
+    }
+
+    /// Sets whether the client keeps the body of the last successful response, for
+    /// debugging.
+    ///
+    /// The body is available through [`Client::last_raw_response`], e.g. to see what
+    /// Porkbun actually sent when it fails to deserialize. Disabled by default.
+    pub fn keep_raw_responses(mut self, keep_raw_responses: bool) -> Self {
+        self.keep_raw_responses = keep_raw_responses;
+        self
+    }
+
+    /// Checks the builder and applies the defaults.
     ///
     /// In the case that no API endpoint is set, the default endpoint of
     /// `https://api.porkbun.com/api/json/v3/` is used.
-    ///
-    /// # Errors
-    /// - `MissingField` if a required field isn't added to the builder.
-    /// - `UrlParse` if the default API endpoint fails to parse. This shouldn't happen.
-    pub fn build(self) -> Result<Client, ClientBuilderError> {
-        let endpoint = match self.endpoint {
+    pub(crate) fn config(self) -> Result<ClientConfig, ClientBuilderError> {
+        let mut endpoint = match self.endpoint {
             Some(endpoint) => endpoint,
-            None => "https://api.porkbun.com/api/json/v3/".parse()?,
+            None => DEFAULT_ENDPOINT.parse()?,
         };
+        if !endpoint.path().ends_with('/') {
+            let path = format!("{}/", endpoint.path());
+            endpoint.set_path(&path);
+        }
         let apikey = self
             .apikey
             .ok_or_else(|| ClientBuilderError::MissingField("apikey".to_string()))?;
@@ -75,27 +498,173 @@ impl ClientBuilder {
             .secretapikey
             .ok_or_else(|| ClientBuilderError::MissingField("secretapikey".to_string()))?;
 
-        Ok(Client::new(endpoint, apikey, secretapikey))
+        Ok(ClientConfig {
+            endpoint,
+            apikey,
+            secretapikey,
+            max_requests: self.max_requests,
+            max_retries: self.max_retries,
+            latch_auth_failures: self.latch_auth_failures,
+            keep_raw_responses: self.keep_raw_responses,
+        })
+    }
+
+    /// Returns a builder for the HTTP client of an async [Client], with the connection
+    /// settings of the builder applied.
+    fn http_client(&self) -> reqwest::ClientBuilder {
+        reqwest::Client::builder().local_address(self.local_address)
+    }
+
+    /// Builds an async [Client] from the builder.
+    ///
+    /// In the case that no API endpoint is set, the default endpoint of
+    /// `https://api.porkbun.com/api/json/v3/` is used.
+    ///
+    /// # Errors
+    /// - `MissingField` if a required field isn't added to the builder.
+    /// - `UrlParse` if the default API endpoint fails to parse. This shouldn't happen.
+    /// - `Reqwest` if the HTTP client fails to initialize.
+    pub fn build_async(self) -> Result<Client, ClientBuilderError> {
+        let client = self.http_client().build()?;
+        let config = self.config()?;
+
+        let mut client =
+            Client::with_client(config.endpoint, config.apikey, config.secretapikey, client)
+                .with_max_requests(config.max_requests);
+        client.max_retries = config.max_retries;
+        client.latch_auth_failures = config.latch_auth_failures;
+        client.keep_raw_responses = config.keep_raw_responses;
+        Ok(client)
+    }
+}
+
+impl ClientBuilder<Client> {
+    /// Builds a [Client] from the builder.
+    ///
+    /// This is the same as [`ClientBuilder::build_async`].
+    ///
+    /// # Errors
+    /// - `MissingField` if a required field isn't added to the builder.
+    /// - `UrlParse` if the default API endpoint fails to parse. This shouldn't happen.
+    /// - `Reqwest` if the HTTP client fails to initialize.
+    pub fn build(self) -> Result<Client, ClientBuilderError> {
+        self.build_async()
+    }
+
+    /// Measures the latency of each of the given endpoints, connecting like a [Client]
+    /// built from the builder would.
+    ///
+    /// This is [`Client::probe_endpoints`], but the requests are made with the connection
+    /// settings of the builder, such as its [local address](ClientBuilder::local_address).
+    /// The API keys aren't needed.
+    pub async fn probe_endpoints(
+        &self,
+        candidates: &[Url],
+        timeout: Duration,
+    ) -> Vec<(Url, Result<Duration, ClientError>)> {
+        let mut results = Vec::with_capacity(candidates.len());
+        for url in candidates {
+            let start = Instant::now();
+            let result = match self.http_client().timeout(timeout).build() {
+                Ok(client) => client.head(url.clone()).send().await,
+                Err(e) => Err(e),
+            };
+            let result = result.map(|_| start.elapsed()).map_err(ClientError::from);
+            results.push((url.clone(), result));
+        }
+        results
     }
 }
 
 /// API client.
 pub struct Client {
     endpoint: Url,
-    apikey: String,
-    secretapikey: String,
+    /// The API keys, ready to be included in payloads.
+    pub(crate) credentials: Credentials,
     client: reqwest::Client,
+    /// The last public IP address returned by [`Client::test_auth`], and when.
+    public_ip: Mutex<Option<(Instant, IpAddr)>>,
+    /// The rate limit budget reported by the last response that included it.
+    rate_limit: Mutex<Option<RateLimitStatus>>,
+    /// The total number of API calls that may be made, if limited.
+    max_requests: Option<usize>,
+    /// The number of API calls that may still be made, if limited.
+    request_budget: Option<AtomicUsize>,
+    /// How many times a rate limited request is retried.
+    max_retries: u32,
+    /// Whether to stop making API calls once the credentials have been rejected.
+    latch_auth_failures: bool,
+    /// Whether the credentials have been rejected, if that is latched.
+    auth_failed: AtomicBool,
+    /// Whether to keep the body of the last successful response.
+    keep_raw_responses: bool,
+    /// The body of the last successful response, if kept.
+    last_raw_response: Mutex<Option<String>>,
 }
 
 impl Client {
     /// Creates a new Client.
     pub fn new(endpoint: Url, apikey: String, secretapikey: String) -> Self {
+        Self::with_client(endpoint, apikey, secretapikey, reqwest::Client::new())
+    }
+
+    /// Creates a new Client using the given HTTP client.
+    fn with_client(
+        endpoint: Url,
+        apikey: String,
+        secretapikey: String,
+        client: reqwest::Client,
+    ) -> Self {
         Self {
             endpoint,
+            credentials: Credentials::new(apikey, secretapikey),
+            client,
+            public_ip: Mutex::new(None),
+            rate_limit: Mutex::new(None),
+            max_requests: None,
+            request_budget: None,
+            max_retries: 0,
+            latch_auth_failures: false,
+            auth_failed: AtomicBool::new(false),
+            keep_raw_responses: false,
+            last_raw_response: Mutex::new(None),
+        }
+    }
+
+    /// Limits the total number of API calls that the client will make.
+    fn with_max_requests(mut self, max_requests: Option<usize>) -> Self {
+        self.max_requests = max_requests;
+        self.request_budget = max_requests.map(AtomicUsize::new);
+        self
+    }
+
+    /// Returns a client for other credentials, sharing this client's connection pool.
+    ///
+    /// This avoids building a new HTTP client for each account in e.g. multi-tenant
+    /// tools. The rest of the configuration, such as the endpoint, is kept. State that
+    /// belongs to the credentials starts afresh: the new client has its own budget of
+    /// [`ClientBuilder::max_requests`] calls, its own rate limit status, and its own
+    /// [latched](ClientBuilder::latch_auth_failures) authentication state.
+    pub fn with_credentials(&self, apikey: String, secretapikey: String) -> Client {
+        let mut client = Self::with_client(
+            self.endpoint.clone(),
             apikey,
             secretapikey,
-            client: reqwest::Client::new(),
-        }
+            self.client.clone(),
+        )
+        .with_max_requests(self.max_requests);
+        client.max_retries = self.max_retries;
+        client.latch_auth_failures = self.latch_auth_failures;
+        client.keep_raw_responses = self.keep_raw_responses;
+        client
+    }
+
+    /// Leaves the failed state that a rejection of the credentials latched the client
+    /// into, so that API calls are made again.
+    ///
+    /// See [`ClientBuilder::latch_auth_failures`].
+    pub fn reset_auth_state(&self) {
+        self.auth_failed.store(false, Ordering::Relaxed);
     }
 
     /// Returns a builder for a Client.
@@ -103,6 +672,11 @@ impl Client {
         ClientBuilder::new()
     }
 
+    /// Returns the API endpoint that the client sends its requests to.
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
     /// Creates a [Url] from the endpoint and the path sections.
     fn build_url(&self, path: &[&str]) -> Result<Url, url::ParseError> {
         path.iter()
@@ -114,25 +688,119 @@ impl Client {
     async fn send_request<T: for<'de> Deserialize<'de>>(
         &self,
         url: Url,
-        payload: Payload,
+        payload: Payload<'_>,
     ) -> Result<T, ClientError> {
-        let resp = self
-            .client
-            .post(url)
-            .json(&JsonValue::from(payload))
-            .send()
-            .await?;
+        let result = self.try_send_request(&url, payload).await;
+        stats::record_result(&self.endpoint, &url, &result);
+        result
+    }
+
+    /// Makes the request for `send_request`, retrying while rate limited.
+    async fn try_send_request<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &Url,
+        payload: Payload<'_>,
+    ) -> Result<T, ClientError> {
+        if self.auth_failed.load(Ordering::Relaxed) {
+            return Err(ClientError::InvalidCredentials);
+        }
+        let mut retries = 0;
+        let resp = loop {
+            if let Some(budget) = &self.request_budget
+                && budget
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                    .is_err()
+            {
+                return Err(ClientError::QuotaExceeded);
+            }
+
+            let resp = self.client.post(url.clone()).json(&payload).send().await?;
+            if let Some(status) = RateLimitStatus::from_headers(resp.headers()) {
+                *self.rate_limit.lock().unwrap_or_else(|e| e.into_inner()) = Some(status);
+            }
+            if resp.status() != StatusCode::TOO_MANY_REQUESTS || retries >= self.max_retries {
+                break resp;
+            }
+
+            stats::record_retry(&self.endpoint, url);
+            let delay = retry_delay(resp.headers(), retries);
+            retries += 1;
+            tokio::time::sleep(delay).await;
+        };
         if resp.status() != StatusCode::OK {
-            return Err(ClientError::Porkbun(ApiError::from_response(resp).await));
+            let err = ApiError::from_response(resp).await;
+            if self.latch_auth_failures && err.is_auth_error() {
+                self.auth_failed.store(true, Ordering::Relaxed);
+            }
+            return Err(ClientError::Porkbun(err));
         }
-        Ok(resp.json().await?)
+        if !self.keep_raw_responses {
+            return Ok(resp.json().await?);
+        }
+
+        let body = resp.text().await?;
+        let result = serde_json::from_str(&body);
+        *self
+            .last_raw_response
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(self.credentials.redact(&body));
+        Ok(result?)
+    }
+
+    /// Returns the body of the last successful response, if the client was built with
+    /// [`ClientBuilder::keep_raw_responses`].
+    ///
+    /// The body is kept even if it failed to deserialize. Any API keys in it are
+    /// redacted, though Porkbun doesn't send them back.
+    pub fn last_raw_response(&self) -> Option<String> {
+        self.last_raw_response
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Returns the rate limit budget reported by the most recent response that included
+    /// one.
+    ///
+    /// Returns `None` if no response has reported it yet; Porkbun doesn't include the
+    /// rate limit headers on every endpoint.
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        *self.rate_limit.lock().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Returns a payload for sending to the Porkbun API.
     ///
     /// This payload already includes the data necessary for authorization.
-    fn payload(&self) -> Payload {
-        Payload::new(&self.apikey, &self.secretapikey)
+    fn payload(&self) -> Payload<'_> {
+        Payload::new(&self.credentials)
+    }
+
+    /// Checks that the API endpoint is reachable.
+    ///
+    /// Sends an unauthenticated `HEAD` request to the endpoint, which establishes a
+    /// connection (including the TLS handshake) without spending an API call. Any HTTP
+    /// response counts as reachable, as this doesn't validate the credentials; use
+    /// [`Client::test_auth`] for that.
+    pub async fn preflight(&self) -> Result<(), ClientError> {
+        self.client.head(self.endpoint.clone()).send().await?;
+        Ok(())
+    }
+
+    /// Measures the latency of each of the given endpoints.
+    ///
+    /// Like [`Client::preflight`], this sends an unauthenticated `HEAD` request to each
+    /// endpoint, and any HTTP response counts as reachable. A new connection is made for
+    /// each endpoint, so the latencies include the TLS handshake and are comparable. This
+    /// can be used to pick e.g. between the default and the IPv4-only endpoint.
+    ///
+    /// Returns each endpoint with its latency, or the error if it wasn't reachable within
+    /// `timeout`. The requests are made with the default connection settings; use
+    /// [`ClientBuilder::probe_endpoints`] to probe with those of a builder instead.
+    pub async fn probe_endpoints(
+        candidates: &[Url],
+        timeout: Duration,
+    ) -> Vec<(Url, Result<Duration, ClientError>)> {
+        Self::builder().probe_endpoints(candidates, timeout).await
     }
 
     /// Calls the endpoint that tests if the authorization is correct.
@@ -152,22 +820,41 @@ impl Client {
         Ok(self.send_request::<Response>(url, payload).await?.your_ip)
     }
 
+    /// Returns the caller's public IP address, reusing a previous result if it is recent.
+    ///
+    /// The result of [`Client::test_auth`] is cached, and only queried again once it is
+    /// older than `max_age`. This avoids spending API calls when polling for IP changes.
+    pub async fn public_ip_cached(&self, max_age: Duration) -> Result<IpAddr, ClientError> {
+        let cached = *self.public_ip.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((fetched, ip)) = cached
+            && fetched.elapsed() <= max_age
+        {
+            return Ok(ip);
+        }
+
+        let ip = self.test_auth().await?;
+        *self.public_ip.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), ip));
+        Ok(ip)
+    }
+
+    /// Creates a DNS record, returning its ID.
     pub async fn create_dns(
         &self,
         domain: &Domain,
         content: &Content,
         ttl: Option<i64>,
-        prio: Option<i64>,
     ) -> Result<i64, ClientError> {
+        validate_record(domain, content)?;
+
         let url = self.build_url(&["dns", "create", domain.root()])?;
 
         let payload = self
             .payload()
             .add("type", content.type_as_str())
-            .add("content", content.value_to_string())
+            .add("content", content.api_value())
             .add_if_some("name", domain.prefix())
             .add_if_some("ttl", ttl)
-            .add_if_some("prio", prio);
+            .add_if_some("prio", content.api_prio());
 
         #[derive(Deserialize)]
         struct Response {
@@ -178,25 +865,208 @@ impl Client {
         Ok(self.send_request::<Response>(url, payload).await?.id)
     }
 
+    /// Creates a DNS record, returning its ID and effective TTL.
+    ///
+    /// Porkbun clamps TTLs to a minimum of 600 seconds, so the effective TTL can differ
+    /// from the requested one. As the create call doesn't report it, the record is
+    /// retrieved after being created. Use [`Client::create_dns`] to avoid the extra call.
+    pub async fn create_dns_record(
+        &self,
+        domain: &Domain,
+        content: &Content,
+        ttl: Option<i64>,
+    ) -> Result<Created, ClientError> {
+        let id = self.create_dns(domain, content, ttl).await?;
+        let records = self.retrieve_dns(domain.root(), Some(id)).await?;
+        let ttl = records
+            .first()
+            .map_or_else(|| ttl.unwrap_or(MIN_TTL).max(MIN_TTL), |r| r.ttl);
+
+        Ok(Created { id, ttl })
+    }
+
+    /// Creates `TXT` records holding the given value, split into chunks of at most 255
+    /// bytes with one record per chunk, returning their IDs in order.
+    ///
+    /// This is all or nothing: if creating any of the chunks fails, the chunks that were
+    /// already created are deleted again before the error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Will return the error of the failed creation. Errors deleting the created chunks
+    /// are ignored, as the original error is more useful.
+    pub async fn create_txt_atomic(
+        &self,
+        domain: &Domain,
+        value: &str,
+    ) -> Result<Vec<i64>, ClientError> {
+        let mut ids = Vec::new();
+        for chunk in record::txt_chunks(value) {
+            let content = Content::Txt(chunk.to_string());
+            match self.create_dns(domain, &content, None).await {
+                Ok(id) => ids.push(id),
+                Err(e) => {
+                    for id in ids {
+                        let _ = self.ensure_dns_absent(domain.root(), id).await;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
     pub async fn edit_dns(
         &self,
         domain: &Domain,
         id: i64,
         content: &Content,
         ttl: Option<i64>,
-        prio: Option<i64>,
     ) -> Result<(), ClientError> {
+        validate_record(domain, content)?;
+
         let url = self.build_url(&["dns", "edit", domain.root(), &id.to_string()])?;
 
         let payload = self
             .payload()
             .add("type", content.type_as_str())
-            .add("content", content.value_to_string())
+            .add("content", content.api_value())
             .add_if_some("name", domain.prefix())
             .add_if_some("ttl", ttl)
-            .add_if_some("prio", prio);
+            .add_if_some("prio", content.api_prio());
 
-        self.send_request(url, payload).await
+        self.send_request::<IgnoredAny>(url, payload).await?;
+        Ok(())
+    }
+
+    /// Edits a DNS record, returning the record as it is after the edit.
+    ///
+    /// As the edit call doesn't report the resulting record, it is retrieved after being
+    /// edited, so that e.g. Porkbun's clamping of the TTL is reflected. Use
+    /// [`Client::edit_dns`] to avoid the extra call.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `Validation` error if the record can't be found after the edit.
+    pub async fn edit_dns_record(
+        &self,
+        domain: &Domain,
+        id: i64,
+        content: &Content,
+        ttl: Option<i64>,
+    ) -> Result<Record, ClientError> {
+        self.edit_dns(domain, id, content, ttl).await?;
+        self.retrieve_dns(domain.root(), Some(id))
+            .await?
+            .into_iter()
+            .find(|r| r.id == id)
+            .ok_or_else(|| {
+                ClientError::Validation(format!("{}: no record with ID {id}", domain.root()))
+            })
+    }
+
+    /// Sets the notes of a DNS record, leaving the rest of it unchanged.
+    ///
+    /// The record is retrieved first, so that its content, TTL and priority can be sent
+    /// again along with the new notes. Notes of `None` clear them.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `Validation` error if there is no record with the given ID.
+    pub async fn set_record_notes(
+        &self,
+        domain: &Domain,
+        id: i64,
+        notes: Option<&str>,
+    ) -> Result<(), ClientError> {
+        let records = self.retrieve_dns(domain.root(), Some(id)).await?;
+        let record = records.iter().find(|r| r.id == id).ok_or_else(|| {
+            ClientError::Validation(format!("{}: no record with ID {id}", domain.root()))
+        })?;
+
+        let url = self.build_url(&["dns", "edit", domain.root(), &id.to_string()])?;
+
+        let content = &record.content;
+        let payload = self
+            .payload()
+            .add("type", content.type_as_str())
+            .add("content", content.api_value())
+            .add_if_some("name", record.name.prefix())
+            .add("ttl", record.ttl)
+            .add_if_some("prio", content.api_prio())
+            .add("notes", notes.unwrap_or(""));
+
+        self.send_request::<IgnoredAny>(url, payload).await?;
+        Ok(())
+    }
+
+    /// Edits a DNS record, unless it already has the given values.
+    ///
+    /// The record is retrieved first, and only edited if its name, content, TTL or
+    /// priority differ from the given ones. This avoids spending an API call (and rate
+    /// limit budget) on edits that wouldn't change anything, e.g. when reconciling. A
+    /// TTL of `None` matches any TTL, and TTLs are compared after Porkbun's clamping.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `Validation` error if there is no record with the given ID.
+    pub async fn edit_dns_if_changed(
+        &self,
+        domain: &Domain,
+        id: i64,
+        content: &Content,
+        ttl: Option<i64>,
+    ) -> Result<UpdateOutcome, ClientError> {
+        validate_record(domain, content)?;
+
+        let records = self.retrieve_dns(domain.root(), Some(id)).await?;
+        let record = records.iter().find(|r| r.id == id).ok_or_else(|| {
+            ClientError::Validation(format!("{}: no record with ID {id}", domain.root()))
+        })?;
+
+        let unchanged = record
+            .name
+            .not_fqdn()
+            .as_str()
+            .eq_ignore_ascii_case(domain.not_fqdn().as_str())
+            && record.content == *content
+            && ttl.is_none_or(|ttl| ttl.max(MIN_TTL) == record.ttl);
+        if unchanged {
+            return Ok(UpdateOutcome::Unchanged);
+        }
+
+        self.edit_dns(domain, id, content, ttl).await?;
+        Ok(UpdateOutcome::Updated)
+    }
+
+    /// Edits a DNS record, if it still has the given version.
+    ///
+    /// The record is retrieved first, and only edited if its [`Record::version`] is
+    /// `expected`, which approximates optimistic concurrency as Porkbun has no support
+    /// for conditional requests. A change made between the retrieval and the edit isn't
+    /// detected.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `Conflict` error if the record has another version, and a
+    /// `Validation` error if there is no record with the given ID.
+    pub async fn edit_if_version(
+        &self,
+        domain: &Domain,
+        id: i64,
+        expected: u64,
+        content: &Content,
+        ttl: Option<i64>,
+    ) -> Result<(), ClientError> {
+        let records = self.retrieve_dns(domain.root(), Some(id)).await?;
+        let record = records.iter().find(|r| r.id == id).ok_or_else(|| {
+            ClientError::Validation(format!("{}: no record with ID {id}", domain.root()))
+        })?;
+        if record.version() != expected {
+            return Err(ClientError::Conflict(id));
+        }
+
+        self.edit_dns(domain, id, content, ttl).await
     }
 
     pub async fn edit_dns_by_name_type(
@@ -204,8 +1074,9 @@ impl Client {
         domain: &Domain,
         content: &Content,
         ttl: Option<i64>,
-        prio: Option<i64>,
     ) -> Result<(), ClientError> {
+        validate_record(domain, content)?;
+
         let url = self.build_url(&[
             "dns",
             "editByNameType",
@@ -216,11 +1087,174 @@ impl Client {
 
         let payload = self
             .payload()
-            .add("content", content.value_to_string())
+            .add("content", content.api_value())
             .add_if_some("ttl", ttl)
-            .add_if_some("prio", prio);
+            .add_if_some("prio", content.api_prio());
+
+        self.send_request::<IgnoredAny>(url, payload).await?;
+        Ok(())
+    }
+
+    /// Points the given domain at the given IP address, creating or editing its record.
+    ///
+    /// An `A` record is used for IPv4 addresses and an `AAAA` record for IPv6 addresses.
+    /// The IP address can come from anywhere, so this doesn't need [`Client::test_auth`]
+    /// for discovering it. If the records of that type already point at the address, no
+    /// change is made; otherwise they are all edited to point at it, or a record is
+    /// created if there are none. Creating a record counts as [`UpdateOutcome::Updated`].
+    pub async fn update_a_record(
+        &self,
+        domain: &Domain,
+        ip: IpAddr,
+        ttl: Option<i64>,
+    ) -> Result<UpdateOutcome, ClientError> {
+        let content: Content = ip.into();
+        let records = self
+            .retrieve_dns_by_name_type(domain, &Type::from(&content))
+            .await?;
+
+        if records.is_empty() {
+            self.create_dns(domain, &content, ttl).await?;
+        } else if records
+            .iter()
+            .all(|r| r.content == content && ttl.is_none_or(|ttl| ttl.max(MIN_TTL) == r.ttl))
+        {
+            return Ok(UpdateOutcome::Unchanged);
+        } else {
+            self.edit_dns_by_name_type(domain, &content, ttl).await?;
+        }
+        Ok(UpdateOutcome::Updated)
+    }
+
+    /// Makes sure that a record with the given content exists at the given domain.
+    ///
+    /// The records of the type of the content are retrieved first. If one of them already
+    /// has the content, TTL and priority, no change is made. Otherwise, the first of them
+    /// is edited, or a record is created if there are none. This is meant for types that
+    /// usually have a single record per name, like `A` and `CNAME`. A TTL of `None`
+    /// matches any TTL, and TTLs are compared after Porkbun's clamping.
+    pub async fn upsert_dns(
+        &self,
+        domain: &Domain,
+        content: &Content,
+        ttl: Option<i64>,
+    ) -> Result<UpsertOutcome, ClientError> {
+        validate_record(domain, content)?;
+
+        let records = self
+            .retrieve_dns_by_name_type(domain, &Type::from(content))
+            .await?;
+
+        let unchanged = records
+            .iter()
+            .find(|r| r.content == *content && ttl.is_none_or(|ttl| ttl.max(MIN_TTL) == r.ttl));
+        let (id, action) = if let Some(record) = unchanged {
+            (record.id, UpsertAction::Unchanged)
+        } else if let Some(record) = records.first() {
+            self.edit_dns(domain, record.id, content, ttl).await?;
+            (record.id, UpsertAction::Updated)
+        } else {
+            let id = self.create_dns(domain, content, ttl).await?;
+            (id, UpsertAction::Created)
+        };
+
+        Ok(UpsertOutcome { id, action })
+    }
+
+    /// Delegates the given subdomain to the given nameservers.
+    ///
+    /// Creates an `NS` record at `domain` for each of the nameservers, returning the IDs
+    /// of the created records in order. This is all or nothing: if creating any of the
+    /// records fails, the records that were already created are deleted again before the
+    /// error is returned.
+    ///
+    /// The zone apex can't be delegated this way. Its `NS` records are the nameservers
+    /// that the registry delegates the domain to, which `NS` records in the zone don't
+    /// change.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `Validation` error if `domain` has no prefix, or the error of the
+    /// failed creation. Errors deleting the created records are ignored, as the original
+    /// error is more useful.
+    pub async fn set_delegation(
+        &self,
+        domain: &Domain,
+        nameservers: &[&Domain],
+    ) -> Result<Vec<i64>, ClientError> {
+        if domain.prefix().is_none() {
+            return Err(ClientError::Validation(format!(
+                "{domain}: the zone apex can't be delegated with NS records"
+            )));
+        }
+
+        let mut ids = Vec::with_capacity(nameservers.len());
+        for nameserver in nameservers {
+            let content = Content::Ns(nameserver.not_fqdn().to_string());
+            match self.create_dns(domain, &content, None).await {
+                Ok(id) => ids.push(id),
+                Err(e) => {
+                    for id in ids {
+                        let _ = self.ensure_dns_absent(domain.root(), id).await;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Replaces all records of the given name and type with records of the given contents.
+    ///
+    /// Unlike [`Client::edit_dns_by_name_type`], which sets every matching record to the
+    /// same content, this makes the set of records exactly match `contents`, which allows
+    /// e.g. round-robin `A` records. Existing records that already have one of the
+    /// contents (and the given TTL, if any) are kept. The missing records are created
+    /// before the remaining old ones are deleted, so that the name doesn't stop resolving
+    /// in between.
+    ///
+    /// Returns the IDs of the records in the resulting set.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `Validation` error if any of the contents isn't of type `type_`.
+    pub async fn replace_records_by_name_type(
+        &self,
+        domain: &Domain,
+        type_: &Type,
+        contents: &[Content],
+        ttl: Option<i64>,
+    ) -> Result<Vec<i64>, ClientError> {
+        if let Some(content) = contents.iter().find(|c| Type::from(*c) != *type_) {
+            return Err(ClientError::Validation(format!(
+                "content of type {} can't replace records of type {}",
+                content.type_as_str(),
+                type_.as_str()
+            )));
+        }
+
+        let mut existing = self.retrieve_dns_by_name_type(domain, type_).await?;
+
+        let mut ids = Vec::with_capacity(contents.len());
+        let mut missing = Vec::new();
+        for content in contents {
+            let kept = existing.iter().position(|r| {
+                r.content == *content && ttl.is_none_or(|ttl| ttl.max(MIN_TTL) == r.ttl)
+            });
+            match kept {
+                Some(i) => ids.push(existing.swap_remove(i).id),
+                None => missing.push(content),
+            }
+        }
+
+        for content in missing {
+            ids.push(self.create_dns(domain, content, ttl).await?);
+        }
+        for record in existing {
+            self.delete_dns(domain.root(), record.id).await?;
+        }
 
-        self.send_request(url, payload).await
+        Ok(ids)
     }
 
     /// Deletes the DNS entry specified by the root of the domain name to be deleted, and its ID.
@@ -229,7 +1263,19 @@ impl Client {
 
         let payload = self.payload();
 
-        self.send_request(url, payload).await
+        self.send_request::<IgnoredAny>(url, payload).await?;
+        Ok(())
+    }
+
+    /// Ensures that the DNS record with the given ID doesn't exist.
+    ///
+    /// Unlike [`Client::delete_dns`], a record that is already gone isn't an error, so
+    /// this can be used to idempotently reconcile records.
+    pub async fn ensure_dns_absent(&self, root: &Root, id: i64) -> Result<(), ClientError> {
+        match self.delete_dns(root, id).await {
+            Err(ClientError::Porkbun(e)) if e.is_not_found() => Ok(()),
+            result => result,
+        }
     }
 
     pub async fn delete_dns_by_name_type(
@@ -247,7 +1293,26 @@ impl Client {
 
         let payload = self.payload();
 
-        self.send_request(url, payload).await
+        self.send_request::<IgnoredAny>(url, payload).await?;
+        Ok(())
+    }
+
+    /// Deletes all records of the given name and type, returning how many were deleted.
+    ///
+    /// The matching records are retrieved first, so that the caller gets feedback on what
+    /// was deleted. If there are none, no delete call is made.
+    pub async fn delete_all_by_name_type(
+        &self,
+        domain: &Domain,
+        type_: &Type,
+    ) -> Result<usize, ClientError> {
+        let records = self.retrieve_dns_by_name_type(domain, type_).await?;
+        if records.is_empty() {
+            return Ok(0);
+        }
+
+        self.delete_dns_by_name_type(domain, type_).await?;
+        Ok(records.len())
     }
 
     /// Retrieves the DNS entry specified by the root of the domain name, and its ID.
@@ -273,6 +1338,31 @@ impl Client {
         Ok(self.send_request::<Response>(url, payload).await?.records)
     }
 
+    /// Retrieves all DNS records of the root, sorted canonically and without duplicates.
+    ///
+    /// See [`record::sort_and_dedup`] for the ordering used.
+    pub async fn retrieve_dns_sorted(&self, root: &Root) -> Result<Vec<Record>, ClientError> {
+        let mut records = self.retrieve_dns(root, None).await?;
+        record::sort_and_dedup(&mut records);
+        Ok(records)
+    }
+
+    /// Retrieves all DNS records of the given domain, regardless of their type.
+    ///
+    /// All records of the root are retrieved, and only those whose name is exactly
+    /// `domain` are returned. A domain without a prefix gives the records at the apex.
+    pub async fn retrieve_dns_by_subdomain(
+        &self,
+        domain: &Domain,
+    ) -> Result<Vec<Record>, ClientError> {
+        let mut records = self.retrieve_dns(domain.root(), None).await?;
+        records.retain(|r| match (r.name.prefix(), domain.prefix()) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            (a, b) => a == b,
+        });
+        Ok(records)
+    }
+
     pub async fn retrieve_dns_by_name_type(
         &self,
         domain: &Domain,
@@ -295,4 +1385,204 @@ impl Client {
 
         Ok(self.send_request::<Response>(url, payload).await?.records)
     }
+
+    /// Checks whether the records of the given domain match the desired ones, without
+    /// changing anything.
+    ///
+    /// Only the records whose name is exactly `domain` are checked, and records that
+    /// look like they are managed by Porkbun itself are ignored (see
+    /// [`record::user_records_only`]). This can be used e.g. for alerting when DNS has
+    /// drifted from the intended configuration.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `Validation` error if any of the desired records has a different
+    /// name than `domain`.
+    pub async fn check_dns<'a>(
+        &self,
+        domain: &Domain,
+        expected: &'a [RecordSpec],
+    ) -> Result<DnsCheck<'a>, ClientError> {
+        if let Some(spec) = expected.iter().find(|s| {
+            !s.name
+                .not_fqdn()
+                .as_str()
+                .eq_ignore_ascii_case(domain.not_fqdn().as_str())
+        }) {
+            return Err(ClientError::Validation(format!(
+                "{}: desired record isn't at {domain}",
+                spec.name
+            )));
+        }
+
+        let records = record::user_records_only(self.retrieve_dns_by_subdomain(domain).await?);
+        Ok(check_records(records, expected))
+    }
+
+    /// Retrieves the authoritative nameservers of the domain.
+    pub async fn get_nameservers(&self, root: &Root) -> Result<Vec<String>, ClientError> {
+        let url = self.build_url(&["domain", "getNs", root])?;
+
+        let payload = self.payload();
+
+        #[derive(Deserialize)]
+        struct Response {
+            ns: Vec<String>,
+        }
+
+        Ok(self.send_request::<Response>(url, payload).await?.ns)
+    }
+
+    /// Returns whether the domain uses Porkbun's nameservers.
+    ///
+    /// Only then will the DNS records managed through the API actually be served.
+    pub async fn uses_porkbun_dns(&self, root: &Root) -> Result<bool, ClientError> {
+        let nameservers = self.get_nameservers(root).await?;
+        Ok(!nameservers.is_empty() && nameservers.iter().all(|ns| is_porkbun_nameserver(ns)))
+    }
+
+    /// Lists the domains in the account.
+    pub async fn list_domains(&self) -> Result<Vec<Box<Root>>, ClientError> {
+        let url = self.build_url(&["domain", "listAll"])?;
+
+        #[derive(Deserialize)]
+        struct DomainInfo {
+            domain: Box<Root>,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(default)]
+            domains: Vec<DomainInfo>,
+        }
+
+        let mut domains = Vec::new();
+        loop {
+            let payload = self.payload().add("start", domains.len().to_string());
+            let page = self
+                .send_request::<Response>(url.clone(), payload)
+                .await?
+                .domains;
+            let done = page.len() < LIST_DOMAINS_PAGE_SIZE;
+            domains.extend(page.into_iter().map(|d| d.domain));
+            if done {
+                return Ok(domains);
+            }
+        }
+    }
+
+    /// Retrieves the DNS records of every domain in the account.
+    ///
+    /// The domains are queried one after another, to stay within Porkbun's rate limits.
+    /// A failure to retrieve the records of a domain doesn't stop the others from being
+    /// retrieved, see [`AccountRecords`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the domains can't be listed.
+    pub async fn retrieve_all_records(&self) -> Result<AccountRecords, ClientError> {
+        let mut all = AccountRecords::default();
+        for root in self.list_domains().await? {
+            match self.retrieve_dns(&root, None).await {
+                Ok(records) => {
+                    all.records.insert(root, records);
+                }
+                Err(e) => all.failures.push((root, e)),
+            }
+        }
+        Ok(all)
+    }
+
+    /// Copies the records of one domain to another, e.g. when moving a site.
+    ///
+    /// The records of `from` are retrieved, and created with the same names, contents and
+    /// TTLs under `to`. Records that are managed by Porkbun itself (see
+    /// [`record::user_records_only`]) are skipped, as `to` has its own. With
+    /// `rewrite_targets`, the targets of `CNAME`, `ALIAS` and `MX` records that point at
+    /// `from` or one of its subdomains are rewritten to the same names under `to`.
+    ///
+    /// Returns the result of creating each copied record, with its ID on success, so that
+    /// one failure doesn't stop the other records from being copied.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the records of `from` can't be retrieved.
+    pub async fn copy_records(
+        &self,
+        from: &Root,
+        to: &Root,
+        rewrite_targets: bool,
+    ) -> Result<Vec<Result<i64, ClientError>>, ClientError> {
+        let records = record::user_records_only(self.retrieve_dns(from, None).await?);
+
+        let mut results = Vec::with_capacity(records.len());
+        for record in &records {
+            let result = match copied_record(record, from, to, rewrite_targets) {
+                Ok((name, content)) => self.create_dns(&name, &content, Some(record.ttl)).await,
+                Err(e) => Err(e),
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Waits until the given content resolves at the given domain, using the system's DNS
+    /// resolver.
+    ///
+    /// Returns whether the content resolved before the timeout elapsed. See
+    /// [`Client::wait_until_resolves_with`] for details.
+    #[cfg(feature = "resolve")]
+    pub async fn wait_until_resolves(
+        &self,
+        domain: &Domain,
+        expected: &Content,
+        timeout: Duration,
+    ) -> Result<bool, ClientError> {
+        resolve::check_resolvable(Type::from(expected))?;
+        let resolver = resolve::system_resolver()?;
+        self.wait_until_resolves_with(&resolver, domain, expected, timeout)
+            .await
+    }
+
+    /// Waits until the given content resolves at the given domain, using the given
+    /// resolver.
+    ///
+    /// This queries DNS rather than the Porkbun API, so it can be used to wait for a
+    /// change to propagate after e.g. [`Client::create_dns`]. The resolver is polled with
+    /// an increasing interval until one of the records of the content's type matches
+    /// `expected`, or until `timeout` elapses.
+    ///
+    /// Returns whether the content resolved before the timeout elapsed.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the resolver fails, or a `Validation` error for `ALIAS`
+    /// content, which can't be resolved.
+    #[cfg(feature = "resolve")]
+    pub async fn wait_until_resolves_with<R: Resolve>(
+        &self,
+        resolver: &R,
+        domain: &Domain,
+        expected: &Content,
+        timeout: Duration,
+    ) -> Result<bool, ClientError> {
+        let type_ = Type::from(expected);
+        resolve::check_resolvable(type_)?;
+
+        let deadline = Instant::now() + timeout;
+        let mut interval = resolve::INITIAL_POLL_INTERVAL;
+        loop {
+            let contents = resolver.resolve(domain, type_).await?;
+            if contents.contains(expected) {
+                return Ok(true);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(false);
+            }
+            tokio::time::sleep(interval.min(remaining)).await;
+            interval = (interval * 2).min(resolve::MAX_POLL_INTERVAL);
+        }
+    }
 }
@@ -1,12 +1,20 @@
+pub mod as_domain;
+#[cfg(feature = "cache")]
+mod cache;
+mod set;
 #[cfg(test)]
 mod tests;
+mod wire;
 
+#[cfg(feature = "idna")]
+use std::borrow::Cow;
 use std::{
     alloc::LayoutError,
-    borrow::Borrow,
     cmp,
-    fmt::{self, Display},
+    fmt::{self, Display, Write},
     hash::{self, Hash},
+    iter::FusedIterator,
+    net::IpAddr,
     ops::Deref,
     ptr::slice_from_raw_parts,
     str::{self, FromStr},
@@ -16,6 +24,11 @@ use serde::{Deserialize, Serialize};
 use simple_dst::{AllocDst, CloneToUninit, Dst, ToOwned};
 use thiserror::Error;
 
+#[cfg(feature = "cache")]
+pub use cache::DomainCache;
+pub use set::DomainSet;
+pub use wire::{WireName, WireNameError};
+
 const MAX_DOMAIN_LEN: usize = 253;
 const MAX_LABEL_LEN: usize = 63;
 
@@ -25,6 +38,9 @@ pub enum DomainParseError {
     /// The domain is empty.
     #[error("domain is empty")]
     Empty,
+    /// The domain bytes aren't valid UTF-8.
+    #[error("domain is not valid UTF-8: {0}")]
+    InvalidUtf8(#[from] str::Utf8Error),
     /// The domain contains an empty label.
     #[error("{domain}: domain contains an empty label")]
     EmptyLabel { domain: String },
@@ -42,12 +58,37 @@ pub enum DomainParseError {
     /// The domain is too long.
     #[error("{domain}: domain is too long")]
     TooLong { domain: String },
+    /// The domain contains a label with an invalid character.
+    #[error("{domain}: domain contains a label with an invalid character {ch:?}: {label}")]
+    InvalidLabelChar {
+        domain: String,
+        label: String,
+        ch: char,
+    },
     /// The domain contains a too-long label.
     #[error("{domain}: domain contains a too-long label: {label}")]
     TooLongLabel { domain: String, label: String },
+    /// The domain contains a wildcard label `*` that isn't the leftmost label of its
+    /// prefix.
+    #[error("{domain}: domain contains a wildcard that isn't its leftmost label below the root")]
+    MisplacedWildcard { domain: String },
+    /// The rightmost label of the domain consists only of digits, so it isn't a valid
+    /// TLD. Only checked when [`ParseOptions::reject_numeric_tld`] is set.
+    #[error("{domain}: domain has an all-numeric top-level label: {label}")]
+    NumericTld { domain: String, label: String },
     /// The domain has an unknown suffix.
     #[error("{domain}: domain has an unknown suffix: {suffix}")]
     UnknownSuffix { domain: String, suffix: String },
+    /// The suffix found for the domain is longer than the domain itself.
+    ///
+    /// This case shouldn't be reachable, but guards against odd psl rules.
+    #[error("{domain}: domain suffix is longer than the domain: {suffix}")]
+    SuffixTooLong { domain: String, suffix: String },
+    /// The domain is an invalid internationalized domain, e.g. one with invalid
+    /// Punycode.
+    #[cfg(feature = "idna")]
+    #[error("{domain}: domain is not a valid internationalized domain")]
+    Idna { domain: String },
 }
 
 /// Errors that can occur when creating a domain instance.
@@ -63,13 +104,105 @@ pub enum DomainCreateError {
     Layout(#[from] LayoutError),
 }
 
+/// Options that control how strictly a domain is parsed.
+///
+/// Used with [`Domain::parse_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Whether labels may contain underscores, as used by e.g. `_dmarc` and SRV records.
+    /// Defaults to `true`.
+    pub allow_underscore: bool,
+    /// Whether to reject domains whose rightmost label consists only of digits, such as
+    /// `1.2.3.4`, which could be mistaken for an IP address. Defaults to `false`.
+    pub reject_numeric_tld: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            allow_underscore: true,
+            reject_numeric_tld: false,
+        }
+    }
+}
+
+/// Formats the given domain, in its Unicode form if the alternate flag is set.
+fn fmt_domain(domain: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    #[cfg(feature = "idna")]
+    if f.alternate() {
+        return idna::domain_to_unicode(domain).0.fmt(f);
+    }
+    domain.fmt(f)
+}
+
+/// Converts an internationalized domain to its ASCII (A-label) form.
+///
+/// ASCII domains are returned as they are, but any A-labels in them must be valid
+/// Punycode.
+#[cfg(feature = "idna")]
+fn to_ascii(domain: &str) -> Result<Cow<'_, str>, DomainParseError> {
+    let invalid = || DomainParseError::Idna {
+        domain: domain.to_string(),
+    };
+
+    if !domain.is_ascii() {
+        return idna::domain_to_ascii(domain)
+            .map(Cow::Owned)
+            .map_err(|_| invalid());
+    }
+
+    let has_a_label = get_not_fqdn(domain).split('.').any(|label| {
+        label
+            .get(..4)
+            .is_some_and(|p| p.eq_ignore_ascii_case("xn--"))
+    });
+    if has_a_label && idna::domain_to_unicode(domain).1.is_err() {
+        return Err(invalid());
+    }
+    Ok(Cow::Borrowed(domain))
+}
+
+/// Compares the given domains ASCII-case-insensitively.
+fn cmp_domain(a: &str, b: &str) -> cmp::Ordering {
+    a.bytes()
+        .map(|b| b.to_ascii_lowercase())
+        .cmp(b.bytes().map(|b| b.to_ascii_lowercase()))
+}
+
+/// Hashes the given domain consistently with comparing it ASCII-case-insensitively.
+fn hash_domain<H: hash::Hasher>(domain: &str, state: &mut H) {
+    domain.len().hash(state);
+    for b in domain.bytes() {
+        b.to_ascii_lowercase().hash(state);
+    }
+}
+
 /// Gets the not-fully-qualified part of the given domain.
 fn get_not_fqdn(s: &str) -> &str {
     s.strip_suffix('.').unwrap_or(s)
 }
 
+/// Returns whether the given character is allowed in a label.
+///
+/// Labels consist of letters, digits and hyphens (LDH), and, unless parsing a host name,
+/// underscores, which are used by e.g. `_dmarc` and SRV records.
+fn is_label_char(ch: char, allow_underscore: bool) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '-' || (allow_underscore && ch == '_')
+}
+
+/// Returns the first character of the given label that isn't allowed in it, if any.
+///
+/// This is the check of the characters of a label shared by everything that validates
+/// domain names, such as hostnames in record content.
+pub(crate) fn invalid_label_char(label: &str, allow_underscore: bool) -> Option<char> {
+    label.chars().find(|&c| !is_label_char(c, allow_underscore))
+}
+
 /// Returns the indices for the `.`s between the prefix and root, and before the suffix.
-fn parse_domain(domain: &str) -> Result<(Option<usize>, usize), DomainParseError> {
+fn parse_domain(
+    domain: &str,
+    options: &ParseOptions,
+) -> Result<(Option<usize>, usize), DomainParseError> {
     let not_fqdn = get_not_fqdn(domain);
 
     if not_fqdn.is_empty() {
@@ -82,7 +215,7 @@ fn parse_domain(domain: &str) -> Result<(Option<usize>, usize), DomainParseError
         });
     }
 
-    for label in not_fqdn.split('.') {
+    for (i, label) in not_fqdn.split('.').enumerate() {
         if label.is_empty() {
             return Err(DomainParseError::EmptyLabel {
                 domain: domain.to_string(),
@@ -92,9 +225,32 @@ fn parse_domain(domain: &str) -> Result<(Option<usize>, usize), DomainParseError
                 domain: domain.to_string(),
                 label: label.to_string(),
             });
+        } else if label == "*" {
+            // A wildcard label, as used in wildcard records, which must be leftmost.
+            if i > 0 {
+                return Err(DomainParseError::MisplacedWildcard {
+                    domain: domain.to_string(),
+                });
+            }
+        } else if let Some(ch) = invalid_label_char(label, options.allow_underscore) {
+            return Err(DomainParseError::InvalidLabelChar {
+                domain: domain.to_string(),
+                label: label.to_string(),
+                ch,
+            });
         }
     }
 
+    if options.reject_numeric_tld
+        && let Some(tld) = not_fqdn.rsplit('.').next()
+        && tld.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(DomainParseError::NumericTld {
+            domain: domain.to_string(),
+            label: tld.to_string(),
+        });
+    }
+
     let suffix =
         psl::suffix(not_fqdn.as_bytes()).ok_or_else(|| DomainParseError::MissingSuffix {
             domain: domain.to_string(),
@@ -109,18 +265,72 @@ fn parse_domain(domain: &str) -> Result<(Option<usize>, usize), DomainParseError
     }
 
     let suffix_len = suffix_str.len();
-    if not_fqdn.len() == suffix_len {
-        return Err(DomainParseError::MissingRoot {
-            domain: domain.to_string(),
-        });
+    match suffix_len.cmp(&not_fqdn.len()) {
+        cmp::Ordering::Greater => {
+            return Err(DomainParseError::SuffixTooLong {
+                domain: domain.to_string(),
+                suffix: suffix_str.to_string(),
+            });
+        }
+        cmp::Ordering::Equal => {
+            return Err(DomainParseError::MissingRoot {
+                domain: domain.to_string(),
+            });
+        }
+        cmp::Ordering::Less => {}
     }
     let suffix_separator_idx = not_fqdn.len() - suffix_len - 1;
     let without_suffix = &not_fqdn[..suffix_separator_idx];
+    let root_separator_idx = without_suffix.rfind('.');
+    if root_separator_idx.is_none() && without_suffix == "*" {
+        // The wildcard would be the root itself, rather than a label below it.
+        return Err(DomainParseError::MisplacedWildcard {
+            domain: domain.to_string(),
+        });
+    }
 
-    Ok((without_suffix.rfind('.'), suffix_separator_idx))
+    Ok((root_separator_idx, suffix_separator_idx))
+}
+
+/// An iterator over the labels of a domain, created by [`Domain::labels`] and
+/// [`Root::labels`].
+///
+/// The empty label after the trailing `.` of a fully-qualified domain isn't yielded.
+/// Iterating from the back walks the labels from right to left.
+#[derive(Debug, Clone)]
+pub struct Labels<'a> {
+    inner: str::Split<'a, char>,
+}
+
+impl<'a> Labels<'a> {
+    /// Creates an iterator over the labels of the given domain.
+    fn new(domain: &'a str) -> Self {
+        Self {
+            inner: get_not_fqdn(domain).split('.'),
+        }
+    }
 }
 
+impl<'a> Iterator for Labels<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl DoubleEndedIterator for Labels<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl FusedIterator for Labels<'_> {}
+
 /// The root part of a domain name.
+///
+/// The generated `Root::new_unchecked` takes the indices of the root and suffix
+/// separators, whether the name was absolute, and the domain string, in that order.
 // LAYOUT: This struct must have the same layout as [`Domain`] so that it can be used to
 // create a [`Root`] without re-allocating.
 #[repr(C)]
@@ -129,12 +339,17 @@ fn parse_domain(domain: &str) -> Result<(Option<usize>, usize), DomainParseError
 pub struct Root {
     root_separator_idx: Option<usize>,
     suffix_separator_idx: usize,
+    /// Whether the domain was parsed from an absolute name, i.e. one with a trailing `.`.
+    absolute: bool,
     domain: str,
 }
 
 impl Root {
     /// Parses a string and creates an owned Root.
     ///
+    /// Leading and trailing ASCII whitespace is ignored, but whitespace within the domain
+    /// is invalid.
+    ///
     /// # Errors
     ///
     /// Will return an error in case the domain is invalid, contains a prefix, or if an
@@ -143,7 +358,11 @@ impl Root {
     where
         A: AllocDst<Self>,
     {
-        let (root_separator_idx, suffix_separator_idx) = parse_domain(input)?;
+        let input = input.trim_ascii();
+        #[cfg(feature = "idna")]
+        let input = &*to_ascii(input)?;
+        let (root_separator_idx, suffix_separator_idx) =
+            parse_domain(input, &ParseOptions::default())?;
         if let Some(root_separator_idx) = root_separator_idx {
             return Err(DomainCreateError::Parse(DomainParseError::HasPrefix {
                 domain: input.to_string(),
@@ -151,7 +370,26 @@ impl Root {
             }));
         }
 
-        Ok(unsafe { Self::new_unchecked(root_separator_idx, suffix_separator_idx, input) }?)
+        let absolute = input.ends_with('.');
+        Ok(unsafe {
+            Self::new_unchecked(root_separator_idx, suffix_separator_idx, absolute, input)
+        }?)
+    }
+
+    /// Parses bytes and creates an owned Root.
+    ///
+    /// This is useful when parsing from a byte buffer, such as a zone file, as the bytes
+    /// are only validated as UTF-8 once, rather than being converted to a string first.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the bytes aren't valid UTF-8, or in the same cases as
+    /// [`Root::parse`].
+    pub fn parse_bytes<A>(input: &[u8]) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Self>,
+    {
+        Self::parse(str::from_utf8(input).map_err(DomainParseError::from)?)
     }
 
     /// Returns a string representing the domain.
@@ -160,9 +398,30 @@ impl Root {
         &self.domain[offset..]
     }
 
+    /// Returns the length of the root in bytes, including a trailing `.`.
+    ///
+    /// This is neither the number of characters (see [`Root::char_count`]) nor the
+    /// number of labels. Unlike `Dst::len`, which is the length of the underlying
+    /// string, it doesn't include the prefix of the domain a root was obtained from.
+    pub fn byte_len(&self) -> usize {
+        self.as_str().len()
+    }
+
+    /// Returns the number of characters in the root, including a trailing `.`.
+    ///
+    /// Parsing stores a domain in its ASCII form, so for parsed domains this is always
+    /// the same as [`Root::byte_len`]. It only differs for a root created with
+    /// `new_unchecked` from a string with non-ASCII characters. It isn't the length of
+    /// the Unicode form, as shown with `{:#}`.
+    pub fn char_count(&self) -> usize {
+        self.as_str().chars().count()
+    }
+
     /// Returns the suffix (TLD) of the domain.
+    ///
+    /// The trailing `.` of a fully-qualified domain isn't part of the suffix.
     pub fn suffix(&self) -> &str {
-        &self.domain[self.suffix_separator_idx + 1..]
+        &self.not_fqdn().domain[self.suffix_separator_idx + 1..]
     }
 
     /// Returns whether the domain is fully-qualified (i.e. ends in a `.`).
@@ -170,19 +429,79 @@ impl Root {
         self.as_str().ends_with('.')
     }
 
+    /// Returns whether the root was parsed from an absolute name (i.e. one ending in a
+    /// `.`).
+    ///
+    /// Unlike [`Root::is_fqdn`], this doesn't depend on the string, so it is kept by
+    /// [`Root::not_fqdn`].
+    pub fn was_fqdn(&self) -> bool {
+        self.absolute
+    }
+
+    /// Returns an iterator over the labels of the root, from left to right.
+    ///
+    /// Use [`Iterator::rev`] to walk them from right to left.
+    pub fn labels(&self) -> Labels<'_> {
+        Labels::new(self.as_str())
+    }
+
+    /// Creates the domain formed by prepending the given prefix to the root, e.g.
+    /// `www.example.com` for `www` and `example.com`.
+    ///
+    /// The prefix may consist of multiple labels. A fully-qualified root gives a
+    /// fully-qualified domain, so `www` and `example.com.` give `www.example.com.`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the prefix contains invalid labels, the resulting
+    /// domain is too long, or if an error occured during allocation.
+    pub fn join<A>(&self, prefix: &str) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Domain>,
+    {
+        let domain = format!("{prefix}.{}", self.as_str());
+        let (root_separator_idx, suffix_separator_idx) =
+            parse_domain(&domain, &ParseOptions::default())?;
+
+        // SAFETY: the indices were found by parsing the domain.
+        Ok(unsafe {
+            Domain::new_unchecked(
+                root_separator_idx,
+                suffix_separator_idx,
+                self.absolute,
+                &domain,
+            )
+        }?)
+    }
+
     // Returns a Root representing the not-fully-qualified part.
     pub fn not_fqdn(&self) -> &Self {
         if !self.is_fqdn() {
             return self;
         }
 
+        // SAFETY: dropping the trailing `.` leaves a valid string, and the stored
+        // indices all point before it.
+        unsafe { self.with_shorter_len(self.len() - 1) }
+    }
+
+    /// Returns the root with its string shortened to `new_len` bytes.
+    ///
+    /// # Safety
+    ///
+    /// The shortened string must still be a valid root, with the stored indices
+    /// pointing within it, like when dropping the trailing `.` of an FQDN.
+    unsafe fn with_shorter_len(&self, new_len: usize) -> &Self {
+        debug_assert!(new_len <= self.len());
+        debug_assert!(self.domain.is_char_boundary(new_len));
+
         // SAFETY: the pointer metadata for the Root type is just the length of the
-        // string in it, so creating a new pointer with the metadata of the length minus
-        // one is safe. Lowering the length by one doesn't influence the stored indices.
+        // string in it, so creating a new pointer with a shorter length points to a
+        // prefix of the same string. The caller ensures that it is still valid.
         unsafe {
             let ptr = (&raw const *self).cast::<()>();
             // FUTURE: switch to using ptr_from_raw_parts when it has stabilised.
-            &*(slice_from_raw_parts(ptr, self.len() - 1) as *const Self)
+            &*(slice_from_raw_parts(ptr, new_len) as *const Self)
         }
     }
 }
@@ -193,9 +512,9 @@ impl AsRef<str> for Root {
     }
 }
 
-impl Borrow<str> for Root {
-    fn borrow(&self) -> &str {
-        self.as_str()
+impl AsRef<[u8]> for Root {
+    fn as_ref(&self) -> &[u8] {
+        self.as_str().as_bytes()
     }
 }
 
@@ -207,9 +526,10 @@ impl Deref for Root {
     }
 }
 
+/// Domains are compared ASCII-case-insensitively, as DNS names are.
 impl PartialEq for Root {
     fn eq(&self, other: &Self) -> bool {
-        self.as_str() == other.as_str()
+        self.as_str().eq_ignore_ascii_case(other.as_str())
     }
 }
 
@@ -223,19 +543,49 @@ impl PartialOrd for Root {
 
 impl Ord for Root {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.as_str().cmp(other.as_str())
+        cmp_domain(self.as_str(), other.as_str())
     }
 }
 
 impl Hash for Root {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        self.as_str().hash(state);
+        hash_domain(self.as_str(), state);
     }
 }
 
+/// Formats the domain as stored, in its ASCII form.
+///
+/// With the alternate flag (`{:#}`) and the `idna` feature, internationalized labels are
+/// rendered in their Unicode form instead.
 impl Display for Root {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.as_str().fmt(f)
+        fmt_domain(self.as_str(), f)
+    }
+}
+
+/// Converts a domain into its root, dropping the prefix.
+///
+/// A domain without a prefix is already a valid root, so its allocation is reused
+/// without copying. Otherwise, a new allocation is made for the trimmed root.
+impl From<Box<Domain>> for Box<Root> {
+    fn from(value: Box<Domain>) -> Self {
+        let Some(root_separator_idx) = value.root_separator_idx else {
+            // SAFETY: Domain and Root have the same layout, and a domain without a
+            // prefix has the same fields as the root it represents.
+            return unsafe { Box::from_raw(Box::into_raw(value) as *mut Root) };
+        };
+
+        let offset = root_separator_idx + 1;
+        // SAFETY: the indices are those of the domain, shifted by the removed prefix.
+        unsafe {
+            Root::new_unchecked(
+                None,
+                value.suffix_separator_idx - offset,
+                value.absolute,
+                &value.domain[offset..],
+            )
+        }
+        .expect("layout of a root can't be larger than that of its domain")
     }
 }
 
@@ -293,6 +643,9 @@ impl Serialize for Box<Root> {
 }
 
 /// A domain name.
+///
+/// The generated `Domain::new_unchecked` takes the indices of the root and suffix
+/// separators, whether the name was absolute, and the domain string, in that order.
 // LAYOUT: This struct must have the same layout as [`Root`] so that it can be used to
 // create a [`Root`] without re-allocating.
 #[repr(C)]
@@ -301,12 +654,21 @@ impl Serialize for Box<Root> {
 pub struct Domain {
     root_separator_idx: Option<usize>,
     suffix_separator_idx: usize,
+    /// Whether the domain was parsed from an absolute name, i.e. one with a trailing `.`.
+    absolute: bool,
     domain: str,
 }
 
 impl Domain {
     /// Parses a string and creates an owned Domain.
     ///
+    /// Leading and trailing ASCII whitespace is ignored, but whitespace within the domain
+    /// is invalid.
+    ///
+    /// With the `idna` feature, which is enabled by default, internationalized domains
+    /// such as `münchen.de` are accepted, and stored in their ASCII (A-label) form, here
+    /// `xn--mnchen-3ya.de`. The length limits apply to the ASCII form.
+    ///
     /// # Errors
     ///
     /// Will return an error in case the domain is invalid or if an error occured during
@@ -315,9 +677,114 @@ impl Domain {
     where
         A: AllocDst<Self>,
     {
-        let (root_separator_idx, suffix_separator_idx) = parse_domain(input)?;
+        Self::parse_with_options(input, ParseOptions::default())
+    }
 
-        Ok(unsafe { Self::new_unchecked(root_separator_idx, suffix_separator_idx, input) }?)
+    /// Parses bytes and creates an owned Domain.
+    ///
+    /// This is useful when parsing from a byte buffer, such as a zone file, as the bytes
+    /// are only validated as UTF-8 once, rather than being converted to a string first.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the bytes aren't valid UTF-8, or in the same cases as
+    /// [`Domain::parse`].
+    pub fn parse_bytes<A>(input: &[u8]) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Self>,
+    {
+        Self::parse(str::from_utf8(input).map_err(DomainParseError::from)?)
+    }
+
+    /// Parses a host name and creates an owned Domain.
+    ///
+    /// Unlike [`Domain::parse`], labels containing underscores are rejected, as they
+    /// aren't valid in host names.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the host name is invalid or if an error occured
+    /// during allocation.
+    pub fn parse_hostname<A>(input: &str) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Self>,
+    {
+        Self::parse_with_options(
+            input,
+            ParseOptions {
+                allow_underscore: false,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Parses a string and creates an owned Domain, keeping whether it was absolute.
+    ///
+    /// Like [`Domain::parse`], the trailing `.` of an absolute name is kept in the string,
+    /// so absolute and relative names are distinct domains. Whether the input was
+    /// absolute is also returned by [`Domain::was_fqdn`], which, unlike
+    /// [`Domain::is_fqdn`], still holds for the domain returned by [`Domain::not_fqdn`].
+    /// This allows e.g. a zone import to distinguish relative and absolute names.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the domain is invalid or if an error occured during
+    /// allocation.
+    pub fn parse_strict_fqdn<A>(input: &str) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Self>,
+    {
+        Self::parse(input)
+    }
+
+    /// Parses a string with the given options and creates an owned Domain.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the domain is invalid under the options or if an
+    /// error occured during allocation.
+    pub fn parse_with_options<A>(input: &str, options: ParseOptions) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Self>,
+    {
+        let input = input.trim_ascii();
+        #[cfg(feature = "idna")]
+        let input = &*to_ascii(input)?;
+        let (root_separator_idx, suffix_separator_idx) = parse_domain(input, &options)?;
+
+        let absolute = input.ends_with('.');
+        Ok(unsafe {
+            Self::new_unchecked(root_separator_idx, suffix_separator_idx, absolute, input)
+        }?)
+    }
+
+    /// Creates the reverse DNS name of an IP address, e.g. `1.2.0.192.in-addr.arpa` for
+    /// `192.0.2.1`, or a name of 32 nibble labels under `ip6.arpa` for an IPv6 address.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case an error occured during allocation.
+    pub fn reverse<A>(ip: IpAddr) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Self>,
+    {
+        let mut name = String::with_capacity(72);
+        match ip {
+            IpAddr::V4(ip) => {
+                for octet in ip.octets().iter().rev() {
+                    write!(name, "{octet}.").expect("writing to a String can't fail");
+                }
+                name.push_str("in-addr.arpa");
+            }
+            IpAddr::V6(ip) => {
+                for octet in ip.octets().iter().rev() {
+                    write!(name, "{:x}.{:x}.", octet & 0xf, octet >> 4)
+                        .expect("writing to a String can't fail");
+                }
+                name.push_str("ip6.arpa");
+            }
+        }
+        Self::parse(&name)
     }
 
     /// Returns a string representing the domain.
@@ -325,6 +792,24 @@ impl Domain {
         &self.domain
     }
 
+    /// Returns the length of the domain in bytes, including a trailing `.`.
+    ///
+    /// This is the same as `Dst::len`, and is neither the number of characters (see
+    /// [`Domain::char_count`]) nor the number of labels.
+    pub fn byte_len(&self) -> usize {
+        self.len()
+    }
+
+    /// Returns the number of characters in the domain, including a trailing `.`.
+    ///
+    /// Parsing stores a domain in its ASCII form, so for parsed domains this is always
+    /// the same as [`Domain::byte_len`]. It only differs for a domain created with
+    /// `new_unchecked` from a string with non-ASCII characters. It isn't the length of
+    /// the Unicode form, as shown with `{:#}`.
+    pub fn char_count(&self) -> usize {
+        self.as_str().chars().count()
+    }
+
     /// Returns the prefix (subdomain) of the domain.
     pub fn prefix(&self) -> Option<&str> {
         self.root_separator_idx.map(|i| &self.domain[..i])
@@ -342,9 +827,95 @@ impl Domain {
         self.root().as_str()
     }
 
+    /// Returns the registrable domain (the root), and whether the domain already is the
+    /// registrable domain, i.e. whether it has no prefix.
+    ///
+    /// This is the boundary that e.g. cookies and certificates are scoped to.
+    pub fn registrable(&self) -> (&Root, bool) {
+        (self.root(), self.prefix().is_none())
+    }
+
+    /// Returns an owned copy of the registrable domain (the root).
+    pub fn registrable_owned(&self) -> Box<Root> {
+        self.root().to_owned()
+    }
+
+    /// Returns an iterator over the labels of the domain, from left to right.
+    ///
+    /// Use [`Iterator::rev`] to walk them from right to left, e.g. to compare suffixes.
+    pub fn labels(&self) -> Labels<'_> {
+        Labels::new(self.as_str())
+    }
+
+    /// Returns the domain in its Unicode form, e.g. `münchen.de` for
+    /// `xn--mnchen-3ya.de`.
+    ///
+    /// This is the same as formatting it with the alternate flag (`{:#}`).
+    #[cfg(feature = "idna")]
+    pub fn to_unicode(&self) -> String {
+        idna::domain_to_unicode(self.as_str()).0
+    }
+
+    /// Returns the domain without its leftmost label, e.g. `example.com` for
+    /// `www.example.com`.
+    ///
+    /// Returns `None` if the domain has no prefix, as the root can't be shortened without
+    /// leaving just the suffix.
+    pub fn strip_leftmost_label(&self) -> Option<Box<Domain>> {
+        let root_separator_idx = self.root_separator_idx?;
+        let offset = self.domain.find('.')? + 1;
+        let root_separator_idx = root_separator_idx.checked_sub(offset);
+
+        // SAFETY: the indices are those of the domain, shifted by the removed label. If
+        // the removed label was the whole prefix, the domain no longer has one.
+        Some(
+            unsafe {
+                Domain::new_unchecked(
+                    root_separator_idx,
+                    self.suffix_separator_idx - offset,
+                    self.absolute,
+                    &self.domain[offset..],
+                )
+            }
+            .expect("layout of a shorter domain can't be larger than that of the domain"),
+        )
+    }
+
+    /// Returns the domain formed by prepending the given subdomain to the root of the
+    /// domain, e.g. `mail.example.com` for `mail` and `www.example.com`.
+    ///
+    /// The subdomain may consist of multiple labels. Whether the domain was absolute is
+    /// kept.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the subdomain contains invalid labels, the resulting
+    /// domain is too long, or if an error occured during allocation.
+    pub fn with_subdomain(&self, sub: &str) -> Result<Box<Domain>, DomainCreateError> {
+        self.with_prefix(sub)
+    }
+
+    /// Creates the domain with its prefix replaced by the given one, or set to it if the
+    /// domain has no prefix.
+    ///
+    /// This is [`Root::join`] on the root of the domain.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the prefix contains invalid labels, the resulting
+    /// domain is too long, or if an error occured during allocation.
+    pub fn with_prefix<A>(&self, prefix: &str) -> Result<A, DomainCreateError>
+    where
+        A: AllocDst<Self>,
+    {
+        self.root().join(prefix)
+    }
+
     /// Returns the suffix (TLD) of the domain.
+    ///
+    /// The trailing `.` of a fully-qualified domain isn't part of the suffix.
     pub fn suffix(&self) -> &str {
-        &self.domain[self.suffix_separator_idx + 1..]
+        &self.not_fqdn().domain[self.suffix_separator_idx + 1..]
     }
 
     /// Returns whether the domain is fully-qualified (i.e. ends in a `'`).
@@ -352,19 +923,65 @@ impl Domain {
         self.as_str().ends_with('.')
     }
 
+    /// Returns whether the domain was parsed from an absolute name (i.e. one ending in a
+    /// `.`).
+    ///
+    /// Unlike [`Domain::is_fqdn`], this doesn't depend on the string, so it is kept by
+    /// [`Domain::not_fqdn`].
+    pub fn was_fqdn(&self) -> bool {
+        self.absolute
+    }
+
     // Returns a Domain representing the not-fully-qualified part.
     pub fn not_fqdn(&self) -> &Self {
         if !self.is_fqdn() {
             return self;
         }
 
+        // SAFETY: dropping the trailing `.` leaves a valid string, and the stored
+        // indices all point before it.
+        unsafe { self.with_shorter_len(self.len() - 1) }
+    }
+
+    /// Returns the canonical form of the domain, for use as e.g. a map key.
+    ///
+    /// This is the domain with its labels ASCII-lowercased and without the trailing `.`
+    /// of a fully-qualified domain, so `www.example.com` for `WWW.Example.COM.`.
+    /// Surrounding whitespace is already trimmed when parsing. The canonical form isn't
+    /// [absolute](Domain::was_fqdn), so that all spellings of a domain are equal in it.
+    pub fn canonical(&self) -> Box<Domain> {
+        let domain = self.not_fqdn().domain.to_ascii_lowercase();
+
+        // SAFETY: lowercasing ASCII doesn't change the byte length of the string or
+        // the validity of its labels, so the indices still point at the separators.
+        unsafe {
+            Domain::new_unchecked(
+                self.root_separator_idx,
+                self.suffix_separator_idx,
+                false,
+                &domain,
+            )
+        }
+        .expect("layout of the domain can't change when lowercasing it")
+    }
+
+    /// Returns the domain with its string shortened to `new_len` bytes.
+    ///
+    /// # Safety
+    ///
+    /// The shortened string must still be a valid domain, with the stored indices
+    /// pointing within it, like when dropping the trailing `.` of an FQDN.
+    unsafe fn with_shorter_len(&self, new_len: usize) -> &Self {
+        debug_assert!(new_len <= self.len());
+        debug_assert!(self.domain.is_char_boundary(new_len));
+
         // SAFETY: the pointer metadata for the Domain type is just the length of the
-        // string in it, so creating a new pointer with the metadata of the length minus
-        // one is safe. Lowering the length by one doesn't influence the stored indices.
+        // string in it, so creating a new pointer with a shorter length points to a
+        // prefix of the same string. The caller ensures that it is still valid.
         unsafe {
             let ptr = (&raw const *self).cast::<()>();
             // FUTURE: switch to using ptr_from_raw_parts when it has stabilised.
-            &*(slice_from_raw_parts(ptr, self.len() - 1) as *const Self)
+            &*(slice_from_raw_parts(ptr, new_len) as *const Self)
         }
     }
 }
@@ -375,9 +992,9 @@ impl AsRef<str> for Domain {
     }
 }
 
-impl Borrow<str> for Domain {
-    fn borrow(&self) -> &str {
-        self.as_str()
+impl AsRef<[u8]> for Domain {
+    fn as_ref(&self) -> &[u8] {
+        self.as_str().as_bytes()
     }
 }
 
@@ -389,9 +1006,10 @@ impl Deref for Domain {
     }
 }
 
+/// Domains are compared ASCII-case-insensitively, as DNS names are.
 impl PartialEq for Domain {
     fn eq(&self, other: &Self) -> bool {
-        self.as_str() == other.as_str()
+        self.as_str().eq_ignore_ascii_case(other.as_str())
     }
 }
 
@@ -405,19 +1023,23 @@ impl PartialOrd for Domain {
 
 impl Ord for Domain {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.as_str().cmp(other.as_str())
+        cmp_domain(self.as_str(), other.as_str())
     }
 }
 
 impl Hash for Domain {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        self.as_str().hash(state);
+        hash_domain(self.as_str(), state);
     }
 }
 
+/// Formats the domain as stored, in its ASCII form.
+///
+/// With the alternate flag (`{:#}`) and the `idna` feature, internationalized labels are
+/// rendered in their Unicode form instead.
 impl Display for Domain {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.as_str().fmt(f)
+        fmt_domain(self.as_str(), f)
     }
 }
 
@@ -0,0 +1,46 @@
+//! Serde helpers for `String` fields that hold a domain.
+//!
+//! The field is validated as a [`Domain`] when deserializing, but kept as a plain
+//! `String` in memory.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     #[serde(with = "hamsando::domain::as_domain")]
+//!     domain: String,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{"domain":"www.example.com"}"#).unwrap();
+//! assert_eq!(config.domain, "www.example.com");
+//! assert!(serde_json::from_str::<Config>(r#"{"domain":"www..example.com"}"#).is_err());
+//! ```
+
+use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+use super::Domain;
+
+/// Serializes the domain as a string.
+pub fn serialize<S>(value: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(value)
+}
+
+/// Deserializes a string, checking that it is a valid domain.
+///
+/// # Errors
+///
+/// Will return an error in case the string isn't a valid domain.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    Domain::parse::<Box<Domain>>(&value).map_err(D::Error::custom)?;
+    Ok(value)
+}
@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use super::{Domain, DomainCreateError};
+
+/// A cached domain, along with when it was last used.
+#[derive(Debug)]
+struct Entry {
+    domain: Arc<Domain>,
+    last_used: u64,
+}
+
+#[derive(Debug)]
+struct Inner {
+    entries: HashMap<Box<str>, Entry>,
+    tick: u64,
+}
+
+/// A cache of parsed domains.
+///
+/// Parsing a domain involves looking up its suffix in the public suffix list. When the
+/// same domains are parsed repeatedly, this cache memoizes the results, handing out
+/// shared [`Arc`]s. Only successfully parsed domains are cached.
+///
+/// When the cache is full, the least recently used domain is evicted. Finding it takes
+/// time linear in the capacity, so the cache is meant for small working sets.
+///
+/// # Examples
+///
+/// ```
+/// use std::{num::NonZeroUsize, sync::Arc};
+///
+/// use hamsando::domain::DomainCache;
+///
+/// let cache = DomainCache::new(NonZeroUsize::new(16).unwrap());
+/// let a = cache.parse("www.example.com").unwrap();
+/// let b = cache.parse("www.example.com").unwrap();
+/// assert!(Arc::ptr_eq(&a, &b));
+/// ```
+#[derive(Debug)]
+pub struct DomainCache {
+    capacity: NonZeroUsize,
+    inner: Mutex<Inner>,
+}
+
+impl DomainCache {
+    /// Creates an empty cache holding at most `capacity` domains.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Inner {
+                entries: HashMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    /// Returns the maximum number of domains in the cache.
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Returns the number of domains in the cache.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all domains from the cache.
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Parses a string into a shared Domain, reusing a cached result if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the domain is invalid or if an error occured during
+    /// allocation.
+    pub fn parse(&self, input: &str) -> Result<Arc<Domain>, DomainCreateError> {
+        let mut inner = self.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if let Some(entry) = inner.entries.get_mut(input) {
+            entry.last_used = tick;
+            return Ok(entry.domain.clone());
+        }
+
+        let domain: Arc<Domain> = Arc::from(Domain::parse::<Box<_>>(input)?);
+
+        if inner.entries.len() >= self.capacity.get() {
+            let oldest = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                inner.entries.remove(&oldest);
+            }
+        }
+        inner.entries.insert(
+            input.into(),
+            Entry {
+                domain: domain.clone(),
+                last_used: tick,
+            },
+        );
+
+        Ok(domain)
+    }
+
+    /// Locks the inner state, recovering from poisoning as it is always consistent.
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,131 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use super::Domain;
+
+/// A node in the reversed-label trie.
+#[derive(Debug, Default, Clone)]
+struct Node {
+    /// Whether a domain ends at this node.
+    terminal: bool,
+    children: HashMap<Box<str>, Node>,
+}
+
+/// A set of domains, optimized for checking whether a domain is covered by any entry.
+///
+/// Domains are stored in a trie keyed on their labels from right to left, so that
+/// entries sharing a suffix share storage, and lookups only need to walk the labels of
+/// the queried domain. Labels are compared ASCII-case-insensitively, and the trailing
+/// `.` of fully-qualified domains is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use hamsando::domain::{Domain, DomainSet};
+///
+/// let mut set = DomainSet::new();
+/// set.insert(&"example.com".parse::<Box<Domain>>().unwrap());
+///
+/// let www: Box<Domain> = "www.example.com".parse().unwrap();
+/// assert!(set.covers(&www));
+/// assert!(!set.contains(&www));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DomainSet {
+    root: Node,
+    len: usize,
+}
+
+/// Returns the domain lowercased and without a trailing `.`, as used for the keys of the
+/// trie.
+///
+/// The domain is only copied if it contains uppercase letters, and then lowercased as a
+/// whole, so that its labels can be borrowed from it by [`reversed_labels`].
+fn lowercase(domain: &Domain) -> Cow<'_, str> {
+    let domain = domain.not_fqdn().as_str();
+    if domain.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(domain.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(domain)
+    }
+}
+
+/// Returns the labels of a domain returned by [`lowercase`], from right to left.
+fn reversed_labels(domain: &str) -> impl Iterator<Item = &str> {
+    domain.rsplit('.')
+}
+
+impl DomainSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of domains in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the set contains no domains.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a domain to the set.
+    ///
+    /// Returns whether the domain was newly inserted.
+    pub fn insert(&mut self, domain: &Domain) -> bool {
+        let domain = lowercase(domain);
+        let node = reversed_labels(&domain).fold(&mut self.root, |node, label| {
+            node.children.entry(label.into()).or_default()
+        });
+        let inserted = !node.terminal;
+        node.terminal = true;
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    /// Returns whether the set contains exactly the given domain.
+    pub fn contains(&self, domain: &Domain) -> bool {
+        let domain = lowercase(domain);
+        let mut node = &self.root;
+        for label in reversed_labels(&domain) {
+            match node.children.get(label) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        node.terminal
+    }
+
+    /// Returns whether the set contains the given domain or any of its ancestors.
+    pub fn covers(&self, domain: &Domain) -> bool {
+        let domain = lowercase(domain);
+        let mut node = &self.root;
+        for label in reversed_labels(&domain) {
+            match node.children.get(label) {
+                Some(child) if child.terminal => return true,
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        false
+    }
+}
+
+impl<'a> Extend<&'a Domain> for DomainSet {
+    fn extend<I: IntoIterator<Item = &'a Domain>>(&mut self, iter: I) {
+        for domain in iter {
+            self.insert(domain);
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a Domain> for DomainSet {
+    fn from_iter<I: IntoIterator<Item = &'a Domain>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
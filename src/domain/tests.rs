@@ -81,6 +81,10 @@ fn root_methods() {
     let root_with_complex_suffix = Root::parse::<Box<_>>("example.co.uk").unwrap();
     assert_eq!(root_with_complex_suffix.as_str(), "example.co.uk");
     assert_eq!(root_with_complex_suffix.suffix(), "co.uk");
+
+    let fqdn_root = Root::parse::<Box<_>>("example.com.").unwrap();
+    assert_eq!(fqdn_root.as_str(), "example.com.");
+    assert_eq!(fqdn_root.suffix(), "com");
 }
 
 #[test]
@@ -130,6 +134,13 @@ fn domain_parsing() {
     // Test for trailing dot
     let domain_with_trailing_dot = Domain::parse::<Box<_>>("example.com.").unwrap();
     assert_eq!(domain_with_trailing_dot.as_str(), "example.com.");
+    assert_eq!(domain_with_trailing_dot.suffix(), "com");
+    assert_eq!(
+        Domain::parse::<Box<_>>("www.example.co.uk.")
+            .unwrap()
+            .suffix(),
+        "co.uk"
+    );
 }
 
 #[test]
@@ -187,4 +198,635 @@ fn error_handling() {
             domain: tld_only.to_string()
         }))
     );
+
+    // Test inputs where the suffix spans the whole domain
+    for suffix_only in ["co.uk", "com.", "co.uk."] {
+        assert_eq!(
+            Domain::parse::<Box<_>>(suffix_only),
+            Err(DomainCreateError::Parse(DomainParseError::MissingRoot {
+                domain: suffix_only.to_string()
+            }))
+        );
+    }
+}
+
+#[test]
+fn domain_set() {
+    let mut set = DomainSet::new();
+    assert!(set.is_empty());
+    assert!(set.insert(&Domain::parse::<Box<_>>("example.com").unwrap()));
+    assert!(!set.insert(&Domain::parse::<Box<_>>("Example.com.").unwrap()));
+    assert!(set.insert(&Domain::parse::<Box<_>>("api.example.org").unwrap()));
+    assert_eq!(set.len(), 2);
+
+    let www = Domain::parse::<Box<_>>("www.example.com").unwrap();
+    assert!(set.covers(&www));
+    assert!(!set.contains(&www));
+
+    let root = Domain::parse::<Box<_>>("example.com").unwrap();
+    assert!(set.covers(&root));
+    assert!(set.contains(&root));
+
+    let upper = Domain::parse::<Box<_>>("WWW.API.Example.ORG.").unwrap();
+    assert!(set.covers(&upper));
+    assert!(!set.contains(&upper));
+    assert!(set.contains(&Domain::parse::<Box<_>>("API.Example.ORG.").unwrap()));
+
+    let dev_api = Domain::parse::<Box<_>>("dev.api.example.org").unwrap();
+    assert!(set.covers(&dev_api));
+    assert!(!set.contains(&dev_api));
+
+    let other_root = Domain::parse::<Box<_>>("example.org").unwrap();
+    assert!(!set.covers(&other_root));
+    assert!(!set.contains(&other_root));
+
+    let similar = Domain::parse::<Box<_>>("notexample.com").unwrap();
+    assert!(!set.covers(&similar));
+}
+
+#[test]
+fn wire_name() {
+    let packet = b"\x03www\x07example\x03com\x00\xde\xad";
+    let name = WireName::parse::<Box<_>>(packet).unwrap();
+    assert_eq!(name.as_bytes(), &packet[..17]);
+    assert_eq!(name.label_count(), 3);
+    assert_eq!(
+        name.labels().collect::<Vec<_>>(),
+        [&b"www"[..], b"example", b"com"]
+    );
+    let domain = name.to_domain::<Box<_>>().unwrap();
+    assert_eq!(domain.as_str(), "www.example.com");
+    assert_eq!(domain.prefix(), Some("www"));
+    assert_eq!(domain.root_str(), "example.com");
+
+    // Root-only name
+    let root = WireName::parse::<Box<_>>(b"\x00").unwrap();
+    assert_eq!(root.label_count(), 0);
+    assert_eq!(
+        root.to_domain::<Box<_>>(),
+        Err(WireNameError::Domain(DomainCreateError::Parse(
+            DomainParseError::Empty
+        )))
+    );
+
+    // Invalid cases
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x07example\x03co"),
+        Err(WireNameError::Truncated)
+    );
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x07example"),
+        Err(WireNameError::Truncated)
+    );
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x03www\xc0\x0c"),
+        Err(WireNameError::Compressed { offset: 4 })
+    );
+    let mut too_long = [&b"\x3f"[..], &[b'a'; 63]].concat().repeat(4);
+    too_long.push(0);
+    assert_eq!(
+        WireName::parse::<Box<_>>(&too_long),
+        Err(WireNameError::TooLong)
+    );
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x03www\x80\x0c"),
+        Err(WireNameError::ReservedLabelType { offset: 4 })
+    );
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x03www\x40\x0c"),
+        Err(WireNameError::ReservedLabelType { offset: 4 })
+    );
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x02\xff\xfe\x03com\x00"),
+        Err(WireNameError::InvalidLabel { offset: 0 })
+    );
+    // A label containing a `.` isn't reinterpreted as two labels.
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x0bwww.example\x03com\x00"),
+        Err(WireNameError::InvalidLabel { offset: 0 })
+    );
+    assert_eq!(
+        WireName::parse::<Box<_>>(b"\x03www\x04 com\x00"),
+        Err(WireNameError::InvalidLabel { offset: 4 })
+    );
+
+    let wildcard = WireName::parse::<Box<_>>(b"\x01*\x07example\x03com\x00").unwrap();
+    assert_eq!(wildcard.label(0), Some(&b"*"[..]));
+    assert_eq!(wildcard.label(2), Some(&b"com"[..]));
+    assert_eq!(wildcard.label(3), None);
+    assert_eq!(
+        wildcard.to_domain::<Box<_>>().unwrap().as_str(),
+        "*.example.com"
+    );
+}
+
+#[test]
+fn as_bytes() {
+    fn key<K: AsRef<[u8]> + ?Sized>(k: &K) -> Vec<u8> {
+        k.as_ref().to_vec()
+    }
+
+    let domain = Domain::parse::<Box<_>>("www.example.com").unwrap();
+    assert_eq!(AsRef::<[u8]>::as_ref(&*domain), b"www.example.com");
+    assert_eq!(AsRef::<[u8]>::as_ref(domain.root()), b"example.com");
+
+    let mut map = std::collections::HashMap::new();
+    map.insert(key(&*domain), 1);
+    map.insert(key(domain.root()), 2);
+    assert_eq!(map.get(&b"www.example.com"[..]), Some(&1));
+    assert_eq!(map.get(&b"example.com"[..]), Some(&2));
+}
+
+#[test]
+fn surrounding_whitespace() {
+    let domain = Domain::parse::<Box<_>>(" example.com\n").unwrap();
+    assert_eq!(domain.as_str(), "example.com");
+    assert_eq!(domain.suffix(), "com");
+
+    let fqdn = Domain::parse::<Box<_>>("\twww.example.com. ").unwrap();
+    assert_eq!(fqdn.as_str(), "www.example.com.");
+    assert_eq!(fqdn.prefix(), Some("www"));
+
+    let root = Root::parse::<Box<_>>("  example.co.uk\r\n").unwrap();
+    assert_eq!(root.as_str(), "example.co.uk");
+
+    assert_eq!(
+        Domain::parse::<Box<_>>("exa mple.com"),
+        Err(DomainCreateError::Parse(
+            DomainParseError::InvalidLabelChar {
+                domain: "exa mple.com".to_string(),
+                label: "exa mple".to_string(),
+                ch: ' ',
+            }
+        ))
+    );
+    assert_eq!(
+        Domain::parse::<Box<_>>(" \n"),
+        Err(DomainCreateError::Parse(DomainParseError::Empty))
+    );
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn domain_cache() {
+    use std::{num::NonZeroUsize, sync::Arc};
+
+    let cache = DomainCache::new(NonZeroUsize::new(2).unwrap());
+    let www = cache.parse("www.example.com").unwrap();
+    assert_eq!(www.as_str(), "www.example.com");
+    assert!(Arc::ptr_eq(&www, &cache.parse("www.example.com").unwrap()));
+
+    // Errors aren't cached
+    assert!(cache.parse("example.invalid").is_err());
+    assert_eq!(cache.len(), 1);
+
+    let api = cache.parse("api.example.com").unwrap();
+    assert_eq!(cache.len(), 2);
+
+    // Use www so that api is the least recently used entry, then evict it
+    cache.parse("www.example.com").unwrap();
+    cache.parse("dev.example.com").unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(Arc::ptr_eq(&www, &cache.parse("www.example.com").unwrap()));
+    assert!(!Arc::ptr_eq(&api, &cache.parse("api.example.com").unwrap()));
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn label_characters() {
+    let dmarc = Domain::parse::<Box<_>>("_dmarc.example.com").unwrap();
+    assert_eq!(dmarc.prefix(), Some("_dmarc"));
+    let srv = Domain::parse::<Box<_>>("_sip._tcp.example.com").unwrap();
+    assert_eq!(srv.prefix(), Some("_sip._tcp"));
+    let wildcard = Domain::parse::<Box<_>>("*.example.com").unwrap();
+    assert_eq!(wildcard.prefix(), Some("*"));
+    let reverse = Domain::parse::<Box<_>>("1.2.0.192.in-addr.arpa").unwrap();
+    assert_eq!(reverse.suffix(), "in-addr.arpa");
+    assert_eq!(
+        Domain::reverse::<Box<_>>("192.0.2.1".parse().unwrap()).unwrap(),
+        reverse
+    );
+    let reverse = Domain::reverse::<Box<_>>("2001:db8::1".parse().unwrap()).unwrap();
+    assert_eq!(
+        reverse.as_str(),
+        "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+    );
+    assert_eq!(reverse.suffix(), "ip6.arpa");
+
+    assert!(Domain::parse_hostname::<Box<_>>("my-host1.example.com").is_ok());
+    assert_eq!(
+        Domain::parse_hostname::<Box<_>>("_dmarc.example.com"),
+        Err(DomainCreateError::Parse(
+            DomainParseError::InvalidLabelChar {
+                domain: "_dmarc.example.com".to_string(),
+                label: "_dmarc".to_string(),
+                ch: '_',
+            }
+        ))
+    );
+    assert_eq!(
+        Domain::parse::<Box<_>>("www!.example.com"),
+        Err(DomainCreateError::Parse(
+            DomainParseError::InvalidLabelChar {
+                domain: "www!.example.com".to_string(),
+                label: "www!".to_string(),
+                ch: '!',
+            }
+        ))
+    );
+    assert!(matches!(
+        Domain::parse::<Box<_>>("a*.example.com"),
+        Err(DomainCreateError::Parse(
+            DomainParseError::InvalidLabelChar { ch: '*', .. }
+        ))
+    ));
+    assert!(Domain::parse::<Box<_>>("*.www.example.com").is_ok());
+    for misplaced in ["*.com", "*.co.uk", "www.*.example.com", "*.*.example.com"] {
+        assert_eq!(
+            Domain::parse::<Box<_>>(misplaced),
+            Err(DomainCreateError::Parse(
+                DomainParseError::MisplacedWildcard {
+                    domain: misplaced.to_string(),
+                }
+            )),
+            "{misplaced}"
+        );
+    }
+}
+
+#[cfg(feature = "idna")]
+#[test]
+fn unicode_display() {
+    let domain = Domain::parse::<Box<_>>("www.xn--bcher-kva.com").unwrap();
+    assert_eq!(format!("{domain}"), "www.xn--bcher-kva.com");
+    assert_eq!(format!("{domain:#}"), "www.bücher.com");
+    assert_eq!(format!("{:#}", domain.root()), "bücher.com");
+
+    let root = Root::parse::<Box<_>>("example.com.").unwrap();
+    assert_eq!(format!("{root:#}"), "example.com.");
+}
+
+#[test]
+fn was_fqdn() {
+    let relative = Domain::parse_strict_fqdn::<Box<_>>("www.example.com").unwrap();
+    let absolute = Domain::parse_strict_fqdn::<Box<_>>("www.example.com.").unwrap();
+    assert!(!relative.was_fqdn());
+    assert!(!relative.is_fqdn());
+    assert!(absolute.was_fqdn());
+    assert!(absolute.is_fqdn());
+    assert_eq!(absolute.as_str(), "www.example.com.");
+    assert_eq!(absolute.to_string(), "www.example.com.");
+    assert_ne!(absolute, relative);
+    assert_eq!(*absolute.not_fqdn(), *relative);
+    assert_eq!(absolute.prefix(), Some("www"));
+    assert_eq!(absolute.suffix(), "com");
+    assert!(absolute.root().was_fqdn());
+
+    let parsed = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    assert_eq!(parsed, absolute);
+    assert!(parsed.was_fqdn());
+    assert!(parsed.not_fqdn().was_fqdn());
+    assert!(!parsed.not_fqdn().is_fqdn());
+    assert!(!Root::parse::<Box<_>>("example.com").unwrap().was_fqdn());
+    assert!(Root::parse::<Box<_>>("example.com.").unwrap().was_fqdn());
+}
+
+#[test]
+fn domain_into_root() {
+    let domain = Domain::parse::<Box<_>>("example.co.uk.").unwrap();
+    let ptr = (&raw const *domain).cast::<u8>();
+    let root: Box<Root> = domain.into();
+    assert_eq!((&raw const *root).cast::<u8>(), ptr);
+    assert_eq!(root.as_str(), "example.co.uk.");
+    assert_eq!(root.suffix(), "co.uk");
+    assert!(root.was_fqdn());
+
+    let domain = Domain::parse::<Box<_>>("www.api.example.co.uk").unwrap();
+    let ptr = (&raw const *domain).cast::<u8>();
+    let root: Box<Root> = domain.into();
+    assert_ne!((&raw const *root).cast::<u8>(), ptr);
+    assert_eq!(root.as_str(), "example.co.uk");
+    assert_eq!(root.suffix(), "co.uk");
+    assert_eq!(root, Root::parse::<Box<_>>("example.co.uk").unwrap());
+}
+
+#[test]
+fn lengths() {
+    let domain = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    assert_eq!(domain.byte_len(), 16);
+    assert_eq!(domain.byte_len(), domain.len());
+    assert_eq!(domain.char_count(), 16);
+    assert_eq!(domain.root().byte_len(), 12);
+    assert_eq!(domain.root().char_count(), 12);
+
+    // Parsing stores domains in their ASCII form, so a multibyte one has to be created
+    // directly.
+    // SAFETY: the indices are those of the separators before the root and the suffix.
+    let unicode =
+        unsafe { Domain::new_unchecked::<Box<_>>(Some(3), 11, false, "www.bücher.com") }.unwrap();
+    assert_eq!(unicode.suffix(), "com");
+    assert_eq!(unicode.byte_len(), 15);
+    assert_eq!(unicode.char_count(), 14);
+    assert_eq!(unicode.root().byte_len(), 11);
+    assert_eq!(unicode.root().char_count(), 10);
+}
+
+#[test]
+fn numeric_tld() {
+    let strict = ParseOptions {
+        reject_numeric_tld: true,
+        ..Default::default()
+    };
+
+    assert!(Domain::parse_with_options::<Box<_>>("example.com", strict).is_ok());
+    assert!(Domain::parse_with_options::<Box<_>>("1.example.com", strict).is_ok());
+    assert!(Domain::parse_with_options::<Box<_>>("1.2.0.192.in-addr.arpa", strict).is_ok());
+    assert_eq!(
+        Domain::parse_with_options::<Box<_>>("1.2.3.4", strict),
+        Err(DomainCreateError::Parse(DomainParseError::NumericTld {
+            domain: "1.2.3.4".to_string(),
+            label: "4".to_string(),
+        }))
+    );
+    assert!(matches!(
+        Domain::parse::<Box<_>>("1.2.3.4"),
+        Err(DomainCreateError::Parse(
+            DomainParseError::UnknownSuffix { .. }
+        ))
+    ));
+}
+
+#[test]
+fn with_shorter_len() {
+    let domain = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    // SAFETY: dropping the trailing `.` leaves a valid domain.
+    let shorter = unsafe { domain.with_shorter_len(domain.len() - 1) };
+    assert_eq!(shorter.as_str(), "www.example.com");
+    assert_eq!(shorter.prefix(), Some("www"));
+    assert_eq!(shorter.suffix(), "com");
+    assert!(shorter.was_fqdn());
+    assert_eq!(shorter, domain.not_fqdn());
+    assert_eq!(
+        shorter.to_owned(),
+        Domain::parse("www.example.com").unwrap()
+    );
+
+    let root = domain.root();
+    // SAFETY: dropping the trailing `.` leaves a valid root.
+    let shorter = unsafe { root.with_shorter_len(root.len() - 1) };
+    assert_eq!(shorter.as_str(), "example.com");
+    assert_eq!(shorter.suffix(), "com");
+    assert_eq!(shorter, root.not_fqdn());
+    assert_eq!(shorter.to_owned(), Root::parse("example.com").unwrap());
+}
+
+#[test]
+fn parse_bytes() {
+    let domain = Domain::parse_bytes::<Box<_>>(b"www.example.com").unwrap();
+    assert_eq!(domain, Domain::parse("www.example.com").unwrap());
+    let root = Root::parse_bytes::<Box<_>>(b" example.com.\n").unwrap();
+    assert_eq!(root, Root::parse("example.com.").unwrap());
+
+    assert!(matches!(
+        Domain::parse_bytes::<Box<_>>(b"www.ex\xffample.com"),
+        Err(DomainCreateError::Parse(DomainParseError::InvalidUtf8(e))) if e.valid_up_to() == 6
+    ));
+    assert!(matches!(
+        Root::parse_bytes::<Box<_>>(b"\xc3.com"),
+        Err(DomainCreateError::Parse(DomainParseError::InvalidUtf8(_)))
+    ));
+    assert_eq!(
+        Domain::parse_bytes::<Box<_>>(b"www.example.com.invalid"),
+        Domain::parse("www.example.com.invalid")
+    );
+}
+
+#[test]
+fn strip_and_prepend_labels() {
+    let domain = Domain::parse::<Box<_>>("a.b.example.co.uk").unwrap();
+    let stripped = domain.strip_leftmost_label().unwrap();
+    assert_eq!(stripped.as_str(), "b.example.co.uk");
+    assert_eq!(stripped.prefix(), Some("b"));
+    assert_eq!(stripped.root_str(), "example.co.uk");
+    assert_eq!(stripped.suffix(), "co.uk");
+
+    let stripped = stripped.strip_leftmost_label().unwrap();
+    assert_eq!(stripped.as_str(), "example.co.uk");
+    assert_eq!(stripped.prefix(), None);
+    assert_eq!(stripped.suffix(), "co.uk");
+    assert!(stripped.strip_leftmost_label().is_none());
+
+    let fqdn = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    let stripped = fqdn.strip_leftmost_label().unwrap();
+    assert_eq!(stripped.as_str(), "example.com.");
+    assert!(stripped.was_fqdn());
+
+    let mail = fqdn.with_subdomain("mail").unwrap();
+    assert_eq!(mail.as_str(), "mail.example.com.");
+    assert_eq!(mail.prefix(), Some("mail"));
+    assert_eq!(mail.root_str(), "example.com.");
+    assert!(mail.was_fqdn());
+
+    let relative = fqdn.not_fqdn();
+    let nested = relative.with_subdomain("a.b").unwrap();
+    assert_eq!(nested.as_str(), "a.b.example.com");
+    assert_eq!(nested.prefix(), Some("a.b"));
+    assert!(nested.was_fqdn());
+
+    assert!(domain.with_subdomain("").is_err());
+    assert!(domain.with_subdomain("not valid").is_err());
+}
+
+#[test]
+fn join_prefix() {
+    let root = Root::parse::<Box<_>>("example.com.").unwrap();
+    let www = root.join::<Box<_>>("www").unwrap();
+    assert_eq!(www.as_str(), "www.example.com.");
+    assert_eq!(www.prefix(), Some("www"));
+    assert_eq!(www.root_str(), "example.com.");
+    assert!(www.was_fqdn());
+
+    let root = Root::parse::<Box<_>>("example.co.uk").unwrap();
+    let nested = root.join::<std::sync::Arc<_>>("a.b").unwrap();
+    assert_eq!(nested.as_str(), "a.b.example.co.uk");
+    assert_eq!(nested.prefix(), Some("a.b"));
+    assert_eq!(nested.suffix(), "co.uk");
+
+    let api = nested.with_prefix::<Box<_>>("api").unwrap();
+    assert_eq!(api.as_str(), "api.example.co.uk");
+    let apex = Domain::parse::<Box<_>>("example.co.uk").unwrap();
+    assert_eq!(apex.with_prefix::<Box<_>>("api").unwrap(), api);
+
+    assert!(root.join::<Box<_>>("").is_err());
+    assert!(root.join::<Box<_>>(&"a".repeat(64)).is_err());
+    let label = "a".repeat(63);
+    let long = [label.as_str(); 4].join(".");
+    assert!(matches!(
+        root.join::<Box<_>>(&long),
+        Err(DomainCreateError::Parse(DomainParseError::TooLong { .. }))
+    ));
+}
+
+#[test]
+fn registrable() {
+    let domain = Domain::parse::<Box<_>>("www.example.co.uk").unwrap();
+    let (root, is_registrable) = domain.registrable();
+    assert_eq!(root.as_str(), "example.co.uk");
+    assert!(!is_registrable);
+
+    let domain = Domain::parse::<Box<_>>("example.com").unwrap();
+    let (root, is_registrable) = domain.registrable();
+    assert_eq!(root.as_str(), "example.com");
+    assert!(is_registrable);
+
+    let owned = Domain::parse::<Box<_>>("a.b.example.co.uk")
+        .unwrap()
+        .registrable_owned();
+    assert_eq!(owned, Root::parse("example.co.uk").unwrap());
+}
+
+#[test]
+fn as_domain() {
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Config {
+        #[serde(with = "as_domain")]
+        domain: String,
+    }
+
+    let config: Config = serde_json::from_str(r#"{"domain":"www.example.com"}"#).unwrap();
+    assert_eq!(config.domain, "www.example.com");
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"domain":"www.example.com"}"#
+    );
+
+    let err = serde_json::from_str::<Config>(r#"{"domain":"www..example.com"}"#).unwrap_err();
+    assert!(err.to_string().contains("empty label"), "{err}");
+    assert!(serde_json::from_str::<Config>(r#"{"domain":"com"}"#).is_err());
+}
+
+#[test]
+fn canonical() {
+    let domain = Domain::parse::<Box<_>>("  WWW.Example.COM.  ").unwrap();
+    let canonical = domain.canonical();
+    assert_eq!(canonical.as_str(), "www.example.com");
+    assert_eq!(canonical.prefix(), Some("www"));
+    assert_eq!(canonical.root_str(), "example.com");
+    assert_eq!(canonical.suffix(), "com");
+    assert!(!canonical.was_fqdn());
+    assert_eq!(
+        Domain::parse::<Box<_>>("www.example.com")
+            .unwrap()
+            .canonical(),
+        canonical
+    );
+}
+
+#[test]
+fn labels() {
+    let domain = Domain::parse::<Box<_>>("dev.api.example.co.uk").unwrap();
+    assert_eq!(
+        domain.labels().collect::<Vec<_>>(),
+        ["dev", "api", "example", "co", "uk"]
+    );
+    assert_eq!(
+        domain.labels().rev().collect::<Vec<_>>(),
+        ["uk", "co", "example", "api", "dev"]
+    );
+
+    let fqdn = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    assert_eq!(fqdn.labels().collect::<Vec<_>>(), ["www", "example", "com"]);
+    assert_eq!(fqdn.labels().next_back(), Some("com"));
+    assert_eq!(fqdn.root().labels().collect::<Vec<_>>(), ["example", "com"]);
+
+    let mut labels = domain.labels();
+    assert_eq!(labels.next(), Some("dev"));
+    assert_eq!(labels.next_back(), Some("uk"));
+    assert_eq!(labels.collect::<Vec<_>>(), ["api", "example", "co"]);
+}
+
+#[cfg(feature = "idna")]
+#[test]
+fn idna() {
+    let domain = Domain::parse::<Box<_>>("www.münchen.de").unwrap();
+    assert_eq!(domain.as_str(), "www.xn--mnchen-3ya.de");
+    assert_eq!(domain.prefix(), Some("www"));
+    assert_eq!(domain.root_str(), "xn--mnchen-3ya.de");
+    assert_eq!(domain.to_unicode(), "www.münchen.de");
+
+    let root = Root::parse::<Box<_>>("MÜNCHEN.de.").unwrap();
+    assert_eq!(root.as_str(), "xn--mnchen-3ya.de.");
+    let domain = Domain::parse_strict_fqdn::<Box<_>>("bücher.com.").unwrap();
+    assert_eq!(domain.as_str(), "xn--bcher-kva.com.");
+    assert!(domain.was_fqdn());
+
+    // ASCII domains are kept as they are.
+    let domain = Domain::parse::<Box<_>>("WWW.xn--bcher-kva.com").unwrap();
+    assert_eq!(domain.as_str(), "WWW.xn--bcher-kva.com");
+
+    // The label limit applies to the encoded form: this label is 60 bytes long, but its
+    // A-label is longer than 63 bytes.
+    let long = format!("{}ü.de", "a".repeat(58));
+    assert!(matches!(
+        Domain::parse::<Box<_>>(&long),
+        Err(DomainCreateError::Parse(
+            DomainParseError::TooLongLabel { .. }
+        ))
+    ));
+
+    assert_eq!(
+        Domain::parse::<Box<_>>("xn--a.com"),
+        Err(DomainCreateError::Parse(DomainParseError::Idna {
+            domain: "xn--a.com".to_string()
+        }))
+    );
+}
+
+#[test]
+fn case_insensitive() {
+    use std::collections::HashSet;
+
+    let mixed: Box<Domain> = "Example.Com".parse().unwrap();
+    let lower: Box<Domain> = "example.com".parse().unwrap();
+    assert_eq!(mixed, lower);
+    assert_eq!(mixed.as_str(), "Example.Com");
+    assert_eq!(mixed.to_string(), "Example.Com");
+    assert_eq!(mixed.cmp(&lower), cmp::Ordering::Equal);
+    assert!(Domain::parse::<Box<_>>("A.example.com").unwrap() < "b.example.com".parse().unwrap());
+
+    let set = HashSet::from([mixed, lower]);
+    assert_eq!(set.len(), 1);
+
+    let mixed: Box<Root> = "EXAMPLE.com".parse().unwrap();
+    let lower: Box<Root> = "example.com".parse().unwrap();
+    assert_eq!(mixed, lower);
+    assert_eq!(HashSet::from([mixed, lower]).len(), 1);
+
+    // A trailing `.` is still significant.
+    assert_ne!(
+        Domain::parse::<Box<_>>("example.com.").unwrap(),
+        "example.com".parse().unwrap()
+    );
+}
+
+#[test]
+fn walk_to_root() {
+    let domain = Domain::parse::<Box<_>>("dev.api.example.com.").unwrap();
+    let mut chain = vec![domain.prefix().map(str::to_string)];
+    let mut current = domain;
+    while let Some(parent) = current.strip_leftmost_label() {
+        assert_eq!(parent.root(), current.root());
+        assert!(parent.was_fqdn());
+        chain.push(parent.prefix().map(str::to_string));
+        current = parent;
+    }
+    assert_eq!(
+        chain,
+        [Some("dev.api".to_string()), Some("api".to_string()), None]
+    );
+    assert_eq!(current.as_str(), "example.com.");
+    assert_eq!(current.root_str(), "example.com.");
 }
@@ -0,0 +1,169 @@
+use std::{alloc::LayoutError, str};
+
+use simple_dst::{AllocDst, CloneToUninit, Dst, ToOwned};
+use thiserror::Error;
+
+use super::{Domain, DomainCreateError, is_label_char};
+
+/// Maximum length of a name in the DNS wire format, including the length bytes.
+const MAX_WIRE_LEN: usize = 255;
+
+/// Errors that can occur when decoding a name in the DNS wire format.
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum WireNameError {
+    /// The buffer ends before the name does.
+    #[error("wire name is truncated")]
+    Truncated,
+    /// The name uses message compression, which isn't supported.
+    #[error("wire name contains a compression pointer at offset {offset}")]
+    Compressed { offset: usize },
+    /// The name contains a label of one of the reserved types `0x40` and `0x80`.
+    #[error("wire name contains a label of a reserved type at offset {offset}")]
+    ReservedLabelType { offset: usize },
+    /// The name is longer than 255 bytes.
+    #[error("wire name is too long")]
+    TooLong,
+    /// A label contains a byte that isn't allowed in a [`Domain`], such as a `.`.
+    #[error("wire name contains an invalid label at offset {offset}")]
+    InvalidLabel { offset: usize },
+    /// The decoded name isn't a valid domain.
+    #[error(transparent)]
+    Domain(#[from] DomainCreateError),
+    /// Failure to calculate the layout of the wire name type.
+    #[error(transparent)]
+    Layout(#[from] LayoutError),
+}
+
+/// A domain name in the uncompressed DNS wire format.
+///
+/// The name is stored as it appears in a DNS message: a sequence of labels, each
+/// preceded by its length, terminated by the zero-length root label. It is preceded by
+/// the offset of each label in the name, so that labels can be accessed directly.
+///
+/// Labels must consist of letters, digits, hyphens and underscores, or be a single `*`,
+/// so that the name can always be converted to a [`Domain`] label for label.
+///
+/// # Examples
+///
+/// ```
+/// use hamsando::domain::{Domain, WireName};
+///
+/// let packet = b"\x03www\x07example\x03com\x00\x00\x01\x00\x01";
+/// let name: Box<WireName> = WireName::parse(packet).unwrap();
+/// assert_eq!(name.label_count(), 3);
+/// assert_eq!(name.label(1), Some(&b"example"[..]));
+///
+/// let domain: Box<Domain> = name.to_domain().unwrap();
+/// assert_eq!(domain.as_str(), "www.example.com");
+/// ```
+#[repr(C)]
+#[derive(Debug, Dst, CloneToUninit, ToOwned)]
+pub struct WireName {
+    label_count: usize,
+    /// The offsets of the labels, one byte each as names are at most 255 bytes long,
+    /// followed by the name.
+    data: [u8],
+}
+
+impl WireName {
+    /// Decodes the name at the start of the given buffer.
+    ///
+    /// Any bytes in the buffer after the name are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the name is truncated, too long, uses message
+    /// compression or a reserved label type, contains an invalid label, or if an error
+    /// occured during allocation.
+    pub fn parse<A>(buf: &[u8]) -> Result<A, WireNameError>
+    where
+        A: AllocDst<Self>,
+    {
+        let mut offset = 0;
+        let mut offsets = Vec::new();
+        loop {
+            let len = *buf.get(offset).ok_or(WireNameError::Truncated)?;
+            match len & 0xC0 {
+                0x00 => {}
+                0xC0 => return Err(WireNameError::Compressed { offset }),
+                _ => return Err(WireNameError::ReservedLabelType { offset }),
+            }
+            let end = offset + 1 + usize::from(len);
+            if end > MAX_WIRE_LEN {
+                return Err(WireNameError::TooLong);
+            }
+            if len == 0 {
+                offset = end;
+                break;
+            }
+            let label = buf.get(offset + 1..end).ok_or(WireNameError::Truncated)?;
+            if label != b"*" && !label.iter().all(|&b| is_label_char(char::from(b), true)) {
+                return Err(WireNameError::InvalidLabel { offset });
+            }
+            offsets.push(u8::try_from(offset).expect("offsets are below MAX_WIRE_LEN"));
+            offset = end;
+        }
+
+        let label_count = offsets.len();
+        offsets.extend_from_slice(&buf[..offset]);
+        // SAFETY: the name has been validated above, and it is preceded by the offsets of
+        // its labels, of which there are label_count.
+        Ok(unsafe { Self::new_unchecked(label_count, &offsets[..]) }?)
+    }
+
+    /// Returns the offsets of the labels in the name.
+    fn offsets(&self) -> &[u8] {
+        &self.data[..self.label_count]
+    }
+
+    /// Returns the encoded name, including the length bytes and the root label.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[self.label_count..]
+    }
+
+    /// Returns the number of labels in the name, not counting the root label.
+    pub fn label_count(&self) -> usize {
+        self.label_count
+    }
+
+    /// Returns the label at the given index, counting from the left.
+    pub fn label(&self, index: usize) -> Option<&[u8]> {
+        let offset = usize::from(*self.offsets().get(index)?);
+        let name = self.as_bytes();
+        let len = usize::from(name[offset]);
+        Some(&name[offset + 1..offset + 1 + len])
+    }
+
+    /// Returns an iterator over the labels of the name, from left to right.
+    pub fn labels(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.label_count).filter_map(|i| self.label(i))
+    }
+
+    /// Converts the name to a [`Domain`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the name isn't a valid domain, e.g. because its
+    /// suffix is unknown, or if an error occured during allocation.
+    pub fn to_domain<A>(&self) -> Result<A, WireNameError>
+    where
+        A: AllocDst<Domain>,
+    {
+        // The labels only contain ASCII characters other than `.`, so joining them can't
+        // change how the name is split into labels.
+        let labels: Vec<_> = self
+            .labels()
+            .map(|label| str::from_utf8(label).expect("labels are validated to be ASCII"))
+            .collect();
+
+        Ok(Domain::parse(&labels.join("."))?)
+    }
+}
+
+impl PartialEq for WireName {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for WireName {}
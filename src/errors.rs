@@ -1,56 +1,184 @@
-use std::net::AddrParseError;
+use std::{
+    io::Read,
+    net::AddrParseError,
+    num::{ParseIntError, TryFromIntError},
+};
 
-use reqwest::{Response, StatusCode, blocking::Response as BlockingResponse};
+use reqwest::{
+    Response, StatusCode,
+    blocking::Response as BlockingResponse,
+    header::{CONTENT_TYPE, HeaderMap},
+};
 use serde::Deserialize;
 use thiserror::Error;
 
+use crate::domain::DomainCreateError;
+use crate::record::Type;
+
 #[derive(Error, Debug)]
 #[error("Porkbun API error: {status} - {message}")]
 pub struct ApiError {
     status: StatusCode,
     message: String,
+    request_id: Option<String>,
+}
+
+/// Maximum number of bytes of an error response body that are captured.
+const MAX_ERROR_BODY_LEN: usize = 8 * 1024;
+
+/// Fragments of the (lowercased) error messages that Porkbun sends for missing items.
+const NOT_FOUND_MESSAGES: [&str; 3] = ["not found", "invalid record id", "could not find"];
+
+/// Headers that may contain an identifier for the request, in order of preference.
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-trace-id", "cf-ray"];
+
+/// Returns whether the response headers declare a non-JSON body, e.g. an HTML error page.
+///
+/// A missing `Content-Type` isn't taken as non-JSON, as the body may still be JSON.
+fn is_non_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| !v.to_ascii_lowercase().contains("json"))
+}
+
+/// Gets the identifier of the request from the response headers, if there is one.
+fn request_id(headers: &HeaderMap) -> Option<String> {
+    REQUEST_ID_HEADERS
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok())
+        .map(str::to_string)
 }
 
 impl ApiError {
-    /// Converts the response from a Porkbun API request to an `ApiError`.
-    pub(crate) async fn from_response(resp: Response) -> Self {
+    /// Returns the HTTP status code of the response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the error message sent by Porkbun.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the identifier of the request, if the response contained one.
+    ///
+    /// This can be included in support requests to Porkbun.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Returns whether the error indicates that the requested item doesn't exist.
+    ///
+    /// Porkbun doesn't consistently use the `404` status for this, so the message is
+    /// checked as well.
+    pub fn is_not_found(&self) -> bool {
+        let message = self.message.to_ascii_lowercase();
+        self.status == StatusCode::NOT_FOUND
+            || NOT_FOUND_MESSAGES.iter().any(|m| message.contains(m))
+    }
+
+    /// Returns whether the error indicates that the credentials were rejected.
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self.status,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        )
+    }
+
+    /// Creates an `ApiError` for a response whose body isn't JSON.
+    ///
+    /// The body isn't included, as it is usually an HTML error page from Porkbun's edge.
+    fn non_json(status: StatusCode, request_id: Option<String>) -> Self {
+        Self {
+            status,
+            message: format!("upstream returned non-JSON (status {})", status.as_u16()),
+            request_id,
+        }
+    }
+
+    /// Creates an `ApiError` from the (possibly truncated) body of a response.
+    fn from_body(
+        status: StatusCode,
+        request_id: Option<String>,
+        body: Result<Vec<u8>, String>,
+    ) -> Self {
         #[derive(Deserialize)]
         struct ErrorResp {
             message: String,
         }
 
-        let status = resp.status();
-        let text = resp
-            .text()
-            .await
-            .unwrap_or_else(|e| format!("unable to read response body: {e}"));
+        let message = match body {
+            Ok(mut body) => {
+                let truncated = body.len() > MAX_ERROR_BODY_LEN;
+                body.truncate(MAX_ERROR_BODY_LEN);
+                let text = String::from_utf8_lossy(&body);
 
-        let message = serde_json::from_str::<ErrorResp>(&text).map_or_else(
-            |e| format!("unable to get error message from {text:?}: {e}"),
-            |r| r.message,
-        );
+                match serde_json::from_slice::<ErrorResp>(&body) {
+                    Ok(r) => r.message,
+                    Err(_) if truncated => format!(
+                        "unable to get error message from {text:?} (truncated to {MAX_ERROR_BODY_LEN} bytes)"
+                    ),
+                    Err(e) => format!("unable to get error message from {text:?}: {e}"),
+                }
+            }
+            Err(e) => format!("unable to read response body: {e}"),
+        };
 
-        Self { status, message }
+        Self {
+            status,
+            message,
+            request_id,
+        }
     }
 
     /// Converts the response from a Porkbun API request to an `ApiError`.
-    pub(crate) fn from_blocking_response(resp: BlockingResponse) -> Self {
-        #[derive(Deserialize)]
-        struct ErrorResp {
-            message: String,
+    ///
+    /// At most [`MAX_ERROR_BODY_LEN`] bytes of the body are read, and none if it isn't
+    /// JSON.
+    pub(crate) async fn from_response(mut resp: Response) -> Self {
+        let status = resp.status();
+        let request_id = request_id(resp.headers());
+        if is_non_json(resp.headers()) {
+            return Self::non_json(status, request_id);
         }
 
+        let mut body = Vec::new();
+        let body = loop {
+            match resp.chunk().await {
+                Ok(Some(chunk)) => {
+                    body.extend_from_slice(&chunk);
+                    if body.len() > MAX_ERROR_BODY_LEN {
+                        break Ok(body);
+                    }
+                }
+                Ok(None) => break Ok(body),
+                Err(e) => break Err(e.to_string()),
+            }
+        };
+
+        Self::from_body(status, request_id, body)
+    }
+
+    /// Converts the response from a Porkbun API request to an `ApiError`.
+    ///
+    /// At most [`MAX_ERROR_BODY_LEN`] bytes of the body are read, and none if it isn't
+    /// JSON.
+    pub(crate) fn from_blocking_response(resp: BlockingResponse) -> Self {
         let status = resp.status();
-        let text = resp
-            .text()
-            .unwrap_or_else(|e| format!("unable to read response body: {e}"));
+        let request_id = request_id(resp.headers());
+        if is_non_json(resp.headers()) {
+            return Self::non_json(status, request_id);
+        }
 
-        let message = serde_json::from_str::<ErrorResp>(&text).map_or_else(
-            |e| format!("unable to get error message from {text:?}: {e}"),
-            |r| r.message,
-        );
+        let mut body = Vec::new();
+        let body = resp
+            .take(MAX_ERROR_BODY_LEN as u64 + 1)
+            .read_to_end(&mut body)
+            .map(|_| body)
+            .map_err(|e| e.to_string());
 
-        Self { status, message }
+        Self::from_body(status, request_id, body)
     }
 }
 
@@ -62,6 +190,19 @@ pub enum ClientError {
     Reqwest(#[from] reqwest::Error),
     #[error(transparent)]
     UrlParse(#[from] url::ParseError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("invalid request: {0}")]
+    Validation(String),
+    #[error("the client's request budget is exhausted")]
+    QuotaExceeded,
+    #[error("record {0} has been changed since its version was read")]
+    Conflict(i64),
+    #[error("the client's credentials were rejected by an earlier request")]
+    InvalidCredentials,
+    #[cfg(feature = "resolve")]
+    #[error(transparent)]
+    Resolve(#[from] hickory_resolver::error::ResolveError),
 }
 
 #[derive(Error, Debug)]
@@ -70,10 +211,59 @@ pub enum ClientBuilderError {
     MissingField(String),
     #[error(transparent)]
     UrlParse(#[from] url::ParseError),
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
 }
 
 #[derive(Error, Debug)]
 pub enum ContentCreationError {
     #[error(transparent)]
     AddrParse(#[from] AddrParseError),
+    #[error(transparent)]
+    Domain(#[from] DomainCreateError),
+    #[error("{0}: content is missing a type prefix")]
+    MissingType(String),
+    #[error("unknown record type: {0}")]
+    UnknownType(String),
+    #[error("{0}: MX content must be of the form \"<priority> <target>\"")]
+    InvalidMx(String),
+    #[error("{0}: CAA content must be of the form \"<flags> <tag> <value>\"")]
+    InvalidCaa(String),
+    #[error("{0}: CAA tag must only consist of ASCII letters and digits")]
+    InvalidCaaTag(String),
+    #[error("{0}: CAA iodef value must be a mailto:, http: or https: URL")]
+    InvalidCaaIodef(String),
+    #[error("{0}: CAA issue value must start with the domain of a CA")]
+    InvalidCaaIssuer(String),
+    #[error("TXT content can't be empty")]
+    EmptyTxt,
+    #[error("{} content can't be empty", type_.as_str())]
+    EmptyContent { type_: Type },
+    #[error("{0}: SRV content must be of the form \"<priority> <weight> <port> <target>\"")]
+    InvalidSrv(String),
+    #[error("{0}: TLSA content must be of the form \"<usage> <selector> <matching type> <data>\"")]
+    InvalidTlsa(String),
+    #[error("{0}: HTTPS and SVCB content must start with \"<priority> <target>\"")]
+    InvalidSvcb(String),
+    #[error("{content}: address is of the wrong family for a {} record", type_.as_str())]
+    AddressFamily { type_: Type, content: String },
+    #[error("{0}: IPv4-mapped addresses aren't allowed in AAAA records, use an A record instead")]
+    MappedAddress(String),
+    #[error(transparent)]
+    ParseInt(#[from] ParseIntError),
+    #[error(transparent)]
+    IntRange(#[from] TryFromIntError),
+}
+
+#[cfg(feature = "hickory")]
+#[derive(Error, Debug)]
+pub enum RDataConversionError {
+    #[error("unsupported record type: {0}")]
+    UnsupportedType(String),
+    #[error("{0}: content isn't valid for its type")]
+    InvalidContent(String),
+    #[error(transparent)]
+    Proto(#[from] hickory_proto::error::ProtoError),
+    #[error(transparent)]
+    Domain(#[from] DomainCreateError),
 }
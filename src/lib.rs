@@ -19,7 +19,7 @@
 //! let my_ip = client.test_auth().unwrap();
 //!
 //! let domain: Box<Domain> = "example.com".parse().unwrap();
-//! let record_id = client.create_dns(&domain, &my_ip.into(), None, None).unwrap();
+//! let record_id = client.create_dns(&domain, &my_ip.into(), None).unwrap();
 //! ```
 //!
 //! [Porkbun API]: https://porkbun.com/api/json/v3/documentation
@@ -31,6 +31,9 @@ pub mod domain;
 mod errors;
 mod payload;
 pub mod record;
+#[cfg(feature = "resolve")]
+pub mod resolve;
+mod stats;
 
 pub use client::*;
 pub use errors::*;
@@ -1,43 +1,97 @@
+#[cfg(test)]
+mod tests;
+
+use std::{borrow::Cow, cmp, collections::BTreeMap};
+
+use serde::Serialize;
 use serde_json::{Map as JsonMap, Value as JsonValue};
 
+/// Authorization details to include in every payload.
+///
+/// These are kept as JSON values, so that building a payload only has to borrow them.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Credentials {
+    apikey: JsonValue,
+    secretapikey: JsonValue,
+}
+
+impl Credentials {
+    /// Creates new credentials from the given API keys.
+    pub(crate) fn new(apikey: String, secretapikey: String) -> Self {
+        Self {
+            apikey: apikey.into(),
+            secretapikey: secretapikey.into(),
+        }
+    }
+
+    /// Replaces every occurrence of the API keys in the text.
+    pub(crate) fn redact(&self, text: &str) -> String {
+        let mut keys: Vec<&str> = [&self.apikey, &self.secretapikey]
+            .into_iter()
+            .filter_map(JsonValue::as_str)
+            .filter(|key| !key.is_empty())
+            .collect();
+        // The longer key goes first, in case it contains the other.
+        keys.sort_by_key(|key| cmp::Reverse(key.len()));
+
+        let mut text = text.to_string();
+        for key in keys {
+            text = text.replace(key, "[REDACTED]");
+        }
+        text
+    }
+}
+
 /// Payload to send to the Porkbun API.
-#[derive(Debug)]
-pub(crate) struct Payload {
-    payload: JsonMap<String, JsonValue>,
+///
+/// The keys are kept sorted, so that the serialized request body is deterministic
+/// regardless of whether serde_json's `preserve_order` feature is enabled.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct Payload<'a> {
+    payload: BTreeMap<&'a str, Cow<'a, JsonValue>>,
 }
 
-impl Payload {
+impl<'a> Payload<'a> {
     /// Creates a new payload, with the given authorization details.
-    pub(crate) fn new(apikey: &str, secretapikey: &str) -> Self {
-        let mut payload = JsonMap::new();
-        payload.insert("apikey".to_string(), apikey.into());
-        payload.insert("secretapikey".to_string(), secretapikey.into());
+    pub(crate) fn new(credentials: &'a Credentials) -> Self {
+        let mut payload = BTreeMap::new();
+        payload.insert("apikey", Cow::Borrowed(&credentials.apikey));
+        payload.insert("secretapikey", Cow::Borrowed(&credentials.secretapikey));
         Self { payload }
     }
 
     /// Adds the given key-value pair.
-    pub(crate) fn add<T: Into<JsonValue>>(mut self, key: &str, value: T) -> Self {
-        self.payload.insert(key.to_string(), value.into());
+    pub(crate) fn add<T: Into<JsonValue>>(mut self, key: &'a str, value: T) -> Self {
+        self.payload.insert(key, Cow::Owned(value.into()));
         self
     }
 
     /// In the case that `value` is some, adds the key-value pair.
-    pub(crate) fn add_if_some<T: Into<JsonValue>>(mut self, key: &str, value: Option<T>) -> Self {
+    pub(crate) fn add_if_some<T: Into<JsonValue>>(
+        mut self,
+        key: &'a str,
+        value: Option<T>,
+    ) -> Self {
         if let Some(value) = value {
-            self.payload.insert(key.to_string(), value.into());
+            self.payload.insert(key, Cow::Owned(value.into()));
         }
         self
     }
 }
 
-impl From<Payload> for JsonValue {
+impl From<Payload<'_>> for JsonValue {
     fn from(value: Payload) -> Self {
-        JsonValue::Object(value.payload)
+        JsonValue::Object(value.into())
     }
 }
 
-impl From<Payload> for JsonMap<String, JsonValue> {
+impl From<Payload<'_>> for JsonMap<String, JsonValue> {
     fn from(value: Payload) -> Self {
-        value.payload
+        value
+            .payload
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.into_owned()))
+            .collect()
     }
 }
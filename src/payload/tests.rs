@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn key_order() {
+    let credentials = Credentials::new("pk1_key".to_string(), "sk1_key".to_string());
+    let payload = Payload::new(&credentials)
+        .add("type", "A")
+        .add("content", "192.0.2.1")
+        .add_if_some("ttl", Some(600))
+        .add_if_some::<i64>("prio", None);
+
+    let body = serde_json::to_string(&payload).unwrap();
+    assert_eq!(
+        body,
+        r#"{"apikey":"pk1_key","content":"192.0.2.1","secretapikey":"sk1_key","ttl":600,"type":"A"}"#
+    );
+}
+
+#[test]
+fn shared_credentials() {
+    let credentials = Credentials::new("pk1_key".to_string(), "sk1_key".to_string());
+    let first = Payload::new(&credentials).add("name", "www");
+    let second = Payload::new(&credentials).add("content", "192.0.2.1");
+
+    assert_eq!(
+        JsonValue::from(first),
+        serde_json::json!({"apikey": "pk1_key", "secretapikey": "sk1_key", "name": "www"})
+    );
+    assert_eq!(
+        JsonValue::from(second),
+        serde_json::json!({"apikey": "pk1_key", "secretapikey": "sk1_key", "content": "192.0.2.1"})
+    );
+}
@@ -1,20 +1,46 @@
 //! Type-safe DNS record.
 
+#[cfg(feature = "hickory")]
+mod hickory;
+#[cfg(test)]
+mod tests;
+
 use std::{
     error::Error,
+    fmt::{self, Display},
+    hash::{DefaultHasher, Hash, Hasher},
+    mem,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
     str::FromStr,
 };
 
-use serde::Deserialize;
-use strum::IntoStaticStr;
+use serde::{Deserialize, Serialize};
+use strum::{EnumString, IntoStaticStr, VariantArray, VariantNames};
 
-use crate::{ContentCreationError, domain::Domain};
+use crate::{
+    ContentCreationError,
+    domain::{Domain, DomainCreateError, invalid_label_char},
+};
 
 /// Possible types a DNS record can have.
-#[derive(Debug, Deserialize, PartialEq, Eq, IntoStaticStr)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    IntoStaticStr,
+    EnumString,
+    VariantArray,
+    VariantNames,
+)]
 #[serde(rename_all = "UPPERCASE")]
-#[strum(serialize_all = "UPPERCASE")]
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
 pub enum Type {
     A,
     Mx,
@@ -35,23 +61,35 @@ impl Type {
     pub fn as_str(&self) -> &'static str {
         self.into()
     }
+
+    /// Returns every type, e.g. for offering a choice of types in a user interface.
+    pub fn all() -> &'static [Type] {
+        <Self as VariantArray>::VARIANTS
+    }
+
+    /// Returns the string representations of every type, in the same order as
+    /// [`Type::all`].
+    pub fn all_str() -> &'static [&'static str] {
+        <Self as VariantNames>::VARIANTS
+    }
 }
 
 impl From<Content> for Type {
     fn from(value: Content) -> Self {
         match value {
             Content::A(_) => Type::A,
-            Content::Mx(_) => Type::Mx,
+            Content::Mx { .. } => Type::Mx,
             Content::Cname(_) => Type::Cname,
             Content::Alias(_) => Type::Alias,
             Content::Txt(_) => Type::Txt,
             Content::Ns(_) => Type::Ns,
             Content::Aaaa(_) => Type::Aaaa,
-            Content::Srv(_) => Type::Srv,
+            Content::Srv { .. } => Type::Srv,
             Content::Tlsa(_) => Type::Tlsa,
-            Content::Caa(_) => Type::Caa,
+            Content::Caa { .. } => Type::Caa,
             Content::Https(_) => Type::Https,
             Content::Svcb(_) => Type::Svcb,
+            Content::Raw { type_, .. } => type_,
         }
     }
 }
@@ -60,17 +98,18 @@ impl From<&Content> for Type {
     fn from(value: &Content) -> Self {
         match value {
             Content::A(_) => Type::A,
-            Content::Mx(_) => Type::Mx,
+            Content::Mx { .. } => Type::Mx,
             Content::Cname(_) => Type::Cname,
             Content::Alias(_) => Type::Alias,
             Content::Txt(_) => Type::Txt,
             Content::Ns(_) => Type::Ns,
             Content::Aaaa(_) => Type::Aaaa,
-            Content::Srv(_) => Type::Srv,
+            Content::Srv { .. } => Type::Srv,
             Content::Tlsa(_) => Type::Tlsa,
-            Content::Caa(_) => Type::Caa,
+            Content::Caa { .. } => Type::Caa,
             Content::Https(_) => Type::Https,
             Content::Svcb(_) => Type::Svcb,
+            Content::Raw { type_, .. } => *type_,
         }
     }
 }
@@ -79,6 +118,10 @@ impl From<&Content> for Type {
 ///
 /// Ensures that each DNS record type contains the appropriate value format.
 ///
+/// Contents are compared semantically: hostnames (for `CNAME`, `ALIAS`, `NS`, and the
+/// targets of `MX` and `SRV`) are compared ASCII-case-insensitively and ignoring a
+/// trailing `.`. [`Hash`] is consistent with this, so contents can be used as map keys.
+///
 /// # Examples
 ///
 /// ```
@@ -90,109 +133,1041 @@ impl From<&Content> for Type {
 ///
 /// assert_eq!(content, Content::A(Ipv4Addr::new(127, 0, 0, 1)));
 /// ```
-#[derive(Debug, PartialEq, Eq, IntoStaticStr)]
-#[strum(serialize_all = "UPPERCASE")]
+#[derive(Debug)]
 pub enum Content {
     A(Ipv4Addr),
-    Mx(String),
+    /// A mail exchange, with its priority (lower is preferred) and target host.
+    ///
+    /// A target of `None` is the `.` of a null MX record, which states that the domain
+    /// doesn't accept mail.
+    Mx {
+        priority: u16,
+        target: Option<Box<Domain>>,
+    },
     Cname(String),
     Alias(String),
     Txt(String),
     Ns(String),
     Aaaa(Ipv6Addr),
-    Srv(String),
+    /// A service location, with its priority (lower is preferred), its weight for
+    /// choosing between targets of the same priority, and the port and target host of the
+    /// service.
+    ///
+    /// A target of `None` is `.`, which states that the service isn't available.
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: Option<Box<Domain>>,
+    },
     Tlsa(String),
-    Caa(String),
+    /// A certification authority authorization, with its flags, property tag and value.
+    ///
+    /// The value is kept without the quotes around it.
+    Caa {
+        flags: u8,
+        tag: CaaTag,
+        value: String,
+    },
     Https(String),
     Svcb(String),
+    /// Content that Porkbun returned for a record, but that isn't valid for its type.
+    ///
+    /// Records can be created outside of this crate, e.g. in Porkbun's web interface,
+    /// with content that [`Content::from`] rejects. Such content is kept as-is, along
+    /// with the `prio` of types that have a [priority](Content::priority), so that a
+    /// single odd record doesn't make retrieving the others fail.
+    Raw {
+        type_: Type,
+        value: String,
+        priority: Option<i64>,
+    },
+}
+
+/// [`Domain`] is unsized, so targets are cloned with [`ToOwned`].
+impl Clone for Content {
+    fn clone(&self) -> Self {
+        let clone_target = |target: &Option<Box<Domain>>| target.as_deref().map(Domain::to_owned);
+        match self {
+            Content::A(addr) => Content::A(*addr),
+            Content::Mx { priority, target } => Content::Mx {
+                priority: *priority,
+                target: clone_target(target),
+            },
+            Content::Cname(value) => Content::Cname(value.clone()),
+            Content::Alias(value) => Content::Alias(value.clone()),
+            Content::Txt(value) => Content::Txt(value.clone()),
+            Content::Ns(value) => Content::Ns(value.clone()),
+            Content::Aaaa(addr) => Content::Aaaa(*addr),
+            Content::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            } => Content::Srv {
+                priority: *priority,
+                weight: *weight,
+                port: *port,
+                target: clone_target(target),
+            },
+            Content::Tlsa(value) => Content::Tlsa(value.clone()),
+            Content::Caa { flags, tag, value } => Content::Caa {
+                flags: *flags,
+                tag: tag.clone(),
+                value: value.clone(),
+            },
+            Content::Https(value) => Content::Https(value.clone()),
+            Content::Svcb(value) => Content::Svcb(value.clone()),
+            Content::Raw {
+                type_,
+                value,
+                priority,
+            } => Content::Raw {
+                type_: *type_,
+                value: value.clone(),
+                priority: *priority,
+            },
+        }
+    }
+}
+
+/// The property tag of a `CAA` record.
+///
+/// Tags are matched ASCII-case-insensitively when parsing, so e.g. `Issue` is parsed as
+/// [`CaaTag::Issue`]. Unknown tags are kept as-is in [`CaaTag::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CaaTag {
+    /// Authorizes a CA to issue certificates for the domain.
+    Issue,
+    /// Authorizes a CA to issue wildcard certificates for the domain.
+    IssueWild,
+    /// Where to report certificate requests that violate the policy.
+    Iodef,
+    Other(String),
+}
+
+impl CaaTag {
+    /// Gets the string representation of the tag.
+    pub fn as_str(&self) -> &str {
+        match self {
+            CaaTag::Issue => "issue",
+            CaaTag::IssueWild => "issuewild",
+            CaaTag::Iodef => "iodef",
+            CaaTag::Other(tag) => tag,
+        }
+    }
+}
+
+impl Display for CaaTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CaaTag {
+    type Err = ContentCreationError;
+
+    /// Parses a tag, which must consist of ASCII letters and digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(ContentCreationError::InvalidCaaTag(s.to_string()));
+        }
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "issue" => CaaTag::Issue,
+            "issuewild" => CaaTag::IssueWild,
+            "iodef" => CaaTag::Iodef,
+            _ => CaaTag::Other(s.to_string()),
+        })
+    }
 }
 
 impl Content {
     /// Gets the string representation of the type of the content.
     pub fn type_as_str(&self) -> &'static str {
-        self.into()
+        Type::from(self).as_str()
+    }
+
+    /// Returns the priority of the content, for types that have one.
+    ///
+    /// Porkbun sends and receives this separately from the content, in the `prio` field.
+    pub fn priority(&self) -> Option<u16> {
+        match self {
+            Content::Mx { priority, .. } | Content::Srv { priority, .. } => Some(*priority),
+            Content::Raw { priority, .. } => priority.and_then(|p| p.try_into().ok()),
+            _ => None,
+        }
     }
 
     /// Converts the value in the content to a string.
+    ///
+    /// This is the form that [`Content::from`] takes, so the string can be parsed back
+    /// into the same content. For types with a [priority](Content::priority), it starts
+    /// with the priority, e.g. `10 mail.example.com`, unlike the `content` field in the
+    /// Porkbun API.
     pub fn value_to_string(&self) -> String {
+        match self.api_prio() {
+            Some(prio) => format!("{prio} {}", self.api_value()),
+            None => self.api_value(),
+        }
+    }
+
+    /// Gets the value of the `content` field in the Porkbun API, which excludes the
+    /// priority.
+    pub(crate) fn api_value(&self) -> String {
         match self {
             Content::A(addr) => addr.to_string(),
-            Content::Mx(value) => value.clone(),
+            Content::Mx { target, .. } => target_str(target).to_string(),
             Content::Cname(value) => value.clone(),
             Content::Alias(value) => value.clone(),
             Content::Txt(value) => value.clone(),
             Content::Ns(value) => value.clone(),
             Content::Aaaa(addr) => addr.to_string(),
-            Content::Srv(value) => value.clone(),
+            Content::Srv {
+                weight,
+                port,
+                target,
+                ..
+            } => format!("{weight} {port} {}", target_str(target)),
             Content::Tlsa(value) => value.clone(),
-            Content::Caa(value) => value.clone(),
+            Content::Caa { flags, tag, value } => format!("{flags} {tag} \"{value}\""),
             Content::Https(value) => value.clone(),
             Content::Svcb(value) => value.clone(),
+            Content::Raw { value, .. } => value.clone(),
+        }
+    }
+
+    /// Gets the value of the `prio` field in the Porkbun API, for types that have one.
+    pub(crate) fn api_prio(&self) -> Option<i64> {
+        match self {
+            Content::Raw { priority, .. } => *priority,
+            _ => self.priority().map(i64::from),
         }
     }
 
     /// Creates a `Content` from a [`Type`] and a string.
+    ///
+    /// This is the inverse of [`Content::value_to_string`]. `SRV` content of the form
+    /// `<priority> <weight> <port> <target>` is parsed into [`Content::Srv`], and other
+    /// `SRV` content is kept as [`Content::Raw`]; use [`Content::from_strict`] to reject
+    /// it instead.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the string isn't a valid value for the type. The
+    /// targets of `NS` records must be valid domains. `MX` content must be of the form
+    /// `<priority> <target>`, where the target is a valid domain or `.`. Addresses must
+    /// be of the family of the type, and `AAAA` content can't be an IPv4-mapped address;
+    /// use an `A` record for that instead.
+    ///
+    /// Porkbun rejects empty content, so content that is empty or only consists of
+    /// whitespace is rejected for every type. For `TXT` content, surrounding quotes are
+    /// ignored for this, so `""` is rejected as well.
+    ///
+    /// `CAA` content must be of the form `<flags> <tag> "<value>"`. The value of an `iodef`
+    /// property must be a `mailto:`, `http:` or `https:` URL, and that of an `issue` or
+    /// `issuewild` property must start with the domain of a CA (or be empty, to allow no
+    /// CA at all), optionally followed by `;` and parameters.
     pub fn from(type_: &Type, content: &str) -> Result<Content, ContentCreationError> {
         Ok(match type_ {
-            Type::A => Content::A(content.parse()?),
-            Type::Mx => Content::Mx(content.to_string()),
+            Type::A => match content.parse::<Ipv4Addr>() {
+                Ok(addr) => Content::A(addr),
+                Err(_) if content.parse::<Ipv6Addr>().is_ok() => {
+                    return Err(ContentCreationError::AddressFamily {
+                        type_: *type_,
+                        content: content.to_string(),
+                    });
+                }
+                Err(e) => return Err(e.into()),
+            },
+            Type::Mx => {
+                let (priority, target) = content
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| ContentCreationError::InvalidMx(content.to_string()))?;
+                Content::Mx {
+                    priority: priority.parse()?,
+                    target: parse_target(target.trim_start())?,
+                }
+            }
+            Type::Cname | Type::Alias | Type::Srv | Type::Tlsa | Type::Https | Type::Svcb
+                if content.trim().is_empty() =>
+            {
+                return Err(ContentCreationError::EmptyContent { type_: *type_ });
+            }
             Type::Cname => Content::Cname(content.to_string()),
             Type::Alias => Content::Alias(content.to_string()),
-            Type::Txt => Content::Txt(content.to_string()),
-            Type::Ns => Content::Ns(content.to_string()),
-            Type::Aaaa => Content::Aaaa(content.parse()?),
-            Type::Srv => Content::Srv(content.to_string()),
+            Type::Txt => {
+                if content.trim().trim_matches('"').trim().is_empty() {
+                    return Err(ContentCreationError::EmptyTxt);
+                }
+                Content::Txt(content.to_string())
+            }
+            Type::Ns => {
+                Domain::parse::<Box<_>>(content)?;
+                Content::Ns(content.to_string())
+            }
+            Type::Aaaa => match content.parse::<Ipv6Addr>() {
+                Ok(addr) if addr.to_ipv4_mapped().is_some() => {
+                    return Err(ContentCreationError::MappedAddress(content.to_string()));
+                }
+                Ok(addr) => Content::Aaaa(addr),
+                Err(_) if content.parse::<Ipv4Addr>().is_ok() => {
+                    return Err(ContentCreationError::AddressFamily {
+                        type_: *type_,
+                        content: content.to_string(),
+                    });
+                }
+                Err(e) => return Err(e.into()),
+            },
+            Type::Srv => parse_srv(content).unwrap_or_else(|_| Content::Raw {
+                type_: *type_,
+                value: content.to_string(),
+                priority: None,
+            }),
             Type::Tlsa => Content::Tlsa(content.to_string()),
-            Type::Caa => Content::Caa(content.to_string()),
+            Type::Caa => parse_caa(content, true)?,
             Type::Https => Content::Https(content.to_string()),
             Type::Svcb => Content::Svcb(content.to_string()),
         })
     }
+
+    /// Creates a `Content` from a [`Type`] and a string, fully validating the string.
+    ///
+    /// [`Content::from`] is lenient: it only parses the parts of the content that it
+    /// needs, and accepts the rest as raw strings, so that it keeps working with values
+    /// that it doesn't know about. This additionally checks that:
+    /// - the targets of `CNAME` and `ALIAS` records are valid domains,
+    /// - `SRV` content is of the form `<priority> <weight> <port> <target>`, where the
+    ///   target is a valid domain or `.` (malformed content is otherwise kept as
+    ///   [`Content::Raw`]),
+    /// - `TLSA` content is of the form `<usage> <selector> <matching type> <data>`, with
+    ///   hexadecimal data, and
+    /// - `HTTPS` and `SVCB` content starts with `<priority> <target>`.
+    ///
+    /// A target of `.` is allowed for `HTTPS` and `SVCB` records, where it has a special
+    /// meaning.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error in case the string isn't a valid value for the type.
+    pub fn from_strict(type_: &Type, content: &str) -> Result<Content, ContentCreationError> {
+        let parsed = match type_ {
+            Type::Srv if !content.trim().is_empty() => parse_srv(content)?,
+            _ => Content::from(type_, content)?,
+        };
+        match &parsed {
+            Content::Cname(host) | Content::Alias(host) => {
+                Domain::parse::<Box<_>>(host)?;
+            }
+            Content::Tlsa(value) => validate_tlsa(value)?,
+            Content::Https(value) | Content::Svcb(value) => validate_svcb(value)?,
+            _ => {}
+        }
+        Ok(parsed)
+    }
+
+    /// Creates an SPF `TXT` record from the given mechanisms.
+    ///
+    /// The mechanisms are joined after the `v=spf1` version tag, so the final mechanism
+    /// should usually be an `all` mechanism. Content longer than 255 bytes is split into
+    /// multiple quoted strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hamsando::record::Content;
+    ///
+    /// let spf = Content::spf(&["mx", "include:_spf.example.net", "~all"]);
+    /// assert_eq!(
+    ///     spf,
+    ///     Content::Txt("v=spf1 mx include:_spf.example.net ~all".to_string())
+    /// );
+    /// assert!(spf.is_spf());
+    /// ```
+    pub fn spf(mechanisms: &[&str]) -> Content {
+        let value = std::iter::once("v=spf1")
+            .chain(mechanisms.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Content::Txt(split_txt(&value))
+    }
+
+    /// Creates a DKIM `TXT` record for the given selector and base64-encoded RSA public
+    /// key.
+    ///
+    /// Returns the prefix that the record should be created at under the signing domain,
+    /// `<selector>._domainkey`, along with the content. Content longer than 255 bytes, as
+    /// is the case for 2048-bit keys, is split into multiple quoted strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hamsando::{
+    ///     domain::{Domain, Root},
+    ///     record::Content,
+    /// };
+    ///
+    /// let (prefix, content) = Content::dkim("mail", "MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQ");
+    /// let root: Box<Root> = Root::parse("example.com").unwrap();
+    /// let domain: Box<Domain> = root.join(&prefix).unwrap();
+    ///
+    /// assert_eq!(domain.as_str(), "mail._domainkey.example.com");
+    /// assert!(content.is_dkim());
+    /// ```
+    pub fn dkim(selector: &str, public_key: &str) -> (String, Content) {
+        (
+            format!("{selector}._domainkey"),
+            Content::Txt(split_txt(&format!("v=DKIM1; k=rsa; p={public_key}"))),
+        )
+    }
+
+    /// Returns whether the content is an SPF `TXT` record.
+    pub fn is_spf(&self) -> bool {
+        match self {
+            Content::Txt(value) => {
+                let value = value.trim_start_matches('"');
+                value
+                    .get(..6)
+                    .is_some_and(|v| v.eq_ignore_ascii_case("v=spf1"))
+                    && value[6..]
+                        .chars()
+                        .next()
+                        .is_none_or(|c| c == ' ' || c == '"')
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether the content is a DKIM `TXT` record.
+    pub fn is_dkim(&self) -> bool {
+        match self {
+            Content::Txt(value) => {
+                let value = value.trim_start_matches('"');
+                value
+                    .get(..7)
+                    .is_some_and(|v| v.eq_ignore_ascii_case("v=DKIM1"))
+                    && value[7..].trim_start().starts_with([';', '"'])
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the length in bytes of `TXT` content, or `None` for other types.
+    ///
+    /// This is the UTF-8 byte length, which is what the limits of `TXT` records apply
+    /// to, rather than the number of characters.
+    pub fn txt_byte_len(&self) -> Option<usize> {
+        match self {
+            Content::Txt(value) => Some(value.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the content is `TXT` content that is too long for a single string.
+    ///
+    /// A single string in a `TXT` record can be at most 255 bytes long, so longer content
+    /// has to be split into multiple strings.
+    pub fn txt_needs_splitting(&self) -> bool {
+        self.txt_byte_len()
+            .is_some_and(|len| len > MAX_TXT_STRING_LEN)
+    }
+}
+
+impl FromStr for Content {
+    type Err = ContentCreationError;
+
+    /// Parses a `Content` from a string of the form `TYPE:value`, e.g. `A:1.2.3.4`.
+    ///
+    /// The string is split on the first `:`, so the value may itself contain colons.
+    /// The type is matched case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (type_, content) = s
+            .split_once(':')
+            .ok_or_else(|| ContentCreationError::MissingType(s.to_string()))?;
+        let type_ = type_
+            .parse()
+            .map_err(|_| ContentCreationError::UnknownType(type_.to_string()))?;
+
+        Content::from(&type_, content)
+    }
+}
+
+/// Gets the part of a hostname that is significant for comparisons.
+fn hostname_key(hostname: &str) -> &str {
+    hostname.strip_suffix('.').unwrap_or(hostname)
+}
+
+/// Hashes a hostname consistently with comparing it using [`hostname_key`] and ASCII
+/// case-insensitivity.
+fn hash_hostname<H: Hasher>(hostname: &str, state: &mut H) {
+    let key = hostname_key(hostname);
+    key.len().hash(state);
+    for b in key.bytes() {
+        b.to_ascii_lowercase().hash(state);
+    }
+}
+
+/// Gets the target of an `MX` or `SRV` record as a string, which is `.` if it has none.
+fn target_str(target: &Option<Box<Domain>>) -> &str {
+    target.as_deref().map_or(".", Domain::as_str)
+}
+
+/// Compares the targets of `MX` or `SRV` records like other hostnames.
+fn targets_eq(a: &Option<Box<Domain>>, b: &Option<Box<Domain>>) -> bool {
+    hostname_key(target_str(a)).eq_ignore_ascii_case(hostname_key(target_str(b)))
+}
+
+/// Parses the target of an `MX` or `SRV` record, where `.` means that it has none.
+fn parse_target(target: &str) -> Result<Option<Box<Domain>>, DomainCreateError> {
+    match target {
+        "." => Ok(None),
+        target => Domain::parse(target).map(Some),
+    }
+}
+
+impl PartialEq for Content {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Content::A(a), Content::A(b)) => a == b,
+            (Content::Aaaa(a), Content::Aaaa(b)) => a == b,
+            (Content::Cname(a), Content::Cname(b))
+            | (Content::Alias(a), Content::Alias(b))
+            | (Content::Ns(a), Content::Ns(b)) => {
+                hostname_key(a).eq_ignore_ascii_case(hostname_key(b))
+            }
+            (
+                Content::Mx {
+                    priority: pa,
+                    target: ta,
+                },
+                Content::Mx {
+                    priority: pb,
+                    target: tb,
+                },
+            ) => pa == pb && targets_eq(ta, tb),
+            (
+                Content::Srv {
+                    priority: pa,
+                    weight: wa,
+                    port: oa,
+                    target: ta,
+                },
+                Content::Srv {
+                    priority: pb,
+                    weight: wb,
+                    port: ob,
+                    target: tb,
+                },
+            ) => (pa, wa, oa) == (pb, wb, ob) && targets_eq(ta, tb),
+            (
+                Content::Caa {
+                    flags: fa,
+                    tag: ta,
+                    value: va,
+                },
+                Content::Caa {
+                    flags: fb,
+                    tag: tb,
+                    value: vb,
+                },
+            ) => (fa, ta, va) == (fb, tb, vb),
+            (Content::Txt(a), Content::Txt(b))
+            | (Content::Tlsa(a), Content::Tlsa(b))
+            | (Content::Https(a), Content::Https(b))
+            | (Content::Svcb(a), Content::Svcb(b)) => a == b,
+            (
+                Content::Raw {
+                    type_: ta,
+                    value: va,
+                    priority: pa,
+                },
+                Content::Raw {
+                    type_: tb,
+                    value: vb,
+                    priority: pb,
+                },
+            ) => (ta, va, pa) == (tb, vb, pb),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Content {}
+
+impl Hash for Content {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Content::A(addr) => addr.hash(state),
+            Content::Aaaa(addr) => addr.hash(state),
+            Content::Cname(value) | Content::Alias(value) | Content::Ns(value) => {
+                hash_hostname(value, state);
+            }
+            Content::Mx { priority, target } => {
+                priority.hash(state);
+                hash_hostname(target_str(target), state);
+            }
+            Content::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                (priority, weight, port).hash(state);
+                hash_hostname(target_str(target), state);
+            }
+            Content::Caa { flags, tag, value } => (flags, tag, value).hash(state),
+            Content::Txt(value)
+            | Content::Tlsa(value)
+            | Content::Https(value)
+            | Content::Svcb(value) => value.hash(state),
+            Content::Raw {
+                type_,
+                value,
+                priority,
+            } => (type_, value, priority).hash(state),
+        }
+    }
+}
+
+/// Maximum length in bytes of a single string in a `TXT` record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// Splits `TXT` content that is too long for a single string into quoted strings.
+fn split_txt(value: &str) -> String {
+    if value.len() <= MAX_TXT_STRING_LEN {
+        return value.to_string();
+    }
+
+    txt_chunks(value)
+        .into_iter()
+        .map(|chunk| format!("\"{chunk}\""))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits `TXT` content into chunks that each fit in a single string.
+///
+/// Characters are never split across chunks, so chunks of content with non-ASCII
+/// characters can be shorter than 255 bytes.
+pub(crate) fn txt_chunks(value: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = value;
+    while rest.len() > MAX_TXT_STRING_LEN {
+        let mut end = MAX_TXT_STRING_LEN;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+/// Checks that a hostname consists of LDH labels, without requiring a known suffix.
+fn is_ldh_hostname(hostname: &str) -> bool {
+    hostname.split('.').all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && invalid_label_char(label, false).is_none()
+    })
 }
 
+/// Parses `SRV` content of the form `<priority> <weight> <port> <target>`.
+fn parse_srv(content: &str) -> Result<Content, ContentCreationError> {
+    let fields: Vec<_> = content.split_whitespace().collect();
+    let [priority, weight, port, target] = fields[..] else {
+        return Err(ContentCreationError::InvalidSrv(content.to_string()));
+    };
+    Ok(Content::Srv {
+        priority: priority.parse()?,
+        weight: weight.parse()?,
+        port: port.parse()?,
+        target: parse_target(target)?,
+    })
+}
+
+/// Parses `CAA` content of the form `<flags> <tag> "<value>"`.
+///
+/// If `check_value` is set, the value is checked with [`validate_caa_value`].
+fn parse_caa(content: &str, check_value: bool) -> Result<Content, ContentCreationError> {
+    let invalid = || ContentCreationError::InvalidCaa(content.to_string());
+
+    let mut parts = content.trim().splitn(3, char::is_whitespace);
+    let (Some(flags), Some(tag), Some(value)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let flags = flags.parse()?;
+    let tag: CaaTag = tag.parse()?;
+    let value = value.trim_start();
+    let value = value
+        .strip_prefix('"')
+        .map(|v| v.strip_suffix('"').ok_or_else(invalid))
+        .transpose()?
+        .unwrap_or(value);
+
+    if check_value {
+        validate_caa_value(&tag, value)?;
+    }
+
+    Ok(Content::Caa {
+        flags,
+        tag,
+        value: value.to_string(),
+    })
+}
+
+/// Validates the value of a `CAA` property according to RFC 8659.
+///
+/// The values of the `iodef`, `issue` and `issuewild` properties are checked. Other
+/// properties are accepted as-is.
+fn validate_caa_value(tag: &CaaTag, value: &str) -> Result<(), ContentCreationError> {
+    match tag {
+        CaaTag::Iodef => {
+            let valid = url::Url::parse(value)
+                .is_ok_and(|url| matches!(url.scheme(), "mailto" | "http" | "https"));
+            if !valid {
+                return Err(ContentCreationError::InvalidCaaIodef(value.to_string()));
+            }
+        }
+        CaaTag::Issue | CaaTag::IssueWild => {
+            let issuer = value
+                .split_once(';')
+                .map_or(value, |(issuer, _)| issuer)
+                .trim();
+            if !issuer.is_empty() && !is_ldh_hostname(issuer) {
+                return Err(ContentCreationError::InvalidCaaIssuer(value.to_string()));
+            }
+        }
+        CaaTag::Other(_) => {}
+    }
+    Ok(())
+}
+
+/// Validates the target of an `HTTPS` or `SVCB` record, which may be `.`.
+fn validate_target(target: &str) -> Result<(), ContentCreationError> {
+    if target != "." {
+        Domain::parse::<Box<_>>(target)?;
+    }
+    Ok(())
+}
+
+/// Validates `TLSA` content of the form `<usage> <selector> <matching type> <data>`.
+fn validate_tlsa(content: &str) -> Result<(), ContentCreationError> {
+    let invalid = || ContentCreationError::InvalidTlsa(content.to_string());
+
+    let mut fields = content.split_whitespace();
+    let (Some(usage), Some(selector), Some(matching_type)) =
+        (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(invalid());
+    };
+    if usage.parse::<u8>()? > 3 || selector.parse::<u8>()? > 1 || matching_type.parse::<u8>()? > 2 {
+        return Err(invalid());
+    }
+    // The data may be split over multiple fields, as in zone files.
+    let data: String = fields.collect();
+    if data.is_empty()
+        || !data.len().is_multiple_of(2)
+        || !data.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// Validates `HTTPS` or `SVCB` content of the form `<priority> <target> [<params>...]`.
+fn validate_svcb(content: &str) -> Result<(), ContentCreationError> {
+    let mut fields = content.split_whitespace();
+    let (Some(priority), Some(target)) = (fields.next(), fields.next()) else {
+        return Err(ContentCreationError::InvalidSvcb(content.to_string()));
+    };
+    priority.parse::<u16>()?;
+    validate_target(target)
+}
+
+/// IPv4-mapped IPv6 addresses are converted to `A` content.
 impl From<IpAddr> for Content {
     fn from(value: IpAddr) -> Self {
-        match value {
+        match value.to_canonical() {
             IpAddr::V4(addr) => Content::A(addr),
             IpAddr::V6(addr) => Content::Aaaa(addr),
         }
     }
 }
 
+impl Content {
+    /// Creates a `Content` from the separate fields that the Porkbun API uses.
+    ///
+    /// For types with a priority, `prio` is combined into the content, defaulting to 0.
+    /// Content that isn't valid for its type becomes [`Content::Raw`].
+    fn from_api(type_: &Type, content: &str, prio: Option<i64>) -> Content {
+        let prio = matches!(type_, Type::Mx | Type::Srv).then(|| prio.unwrap_or(0));
+        let parsed = match type_ {
+            // Records that were created with a mapped address elsewhere are kept as `AAAA`.
+            Type::Aaaa => content.parse().map(Content::Aaaa).map_err(Into::into),
+            // And CAA records with values that don't pass validation as `CAA`.
+            Type::Caa => parse_caa(content, false),
+            Type::Srv => parse_srv(&format!("{} {content}", prio.unwrap_or(0))),
+            _ => match prio {
+                Some(prio) => Content::from(type_, &format!("{prio} {content}")),
+                None => Content::from(type_, content),
+            },
+        };
+        parsed.unwrap_or_else(|_| Content::Raw {
+            type_: *type_,
+            value: content.to_string(),
+            priority: prio,
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for Content {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        use serde::de::Error;
-
         #[derive(Deserialize)]
         struct ContentDeserializable {
             #[serde(rename = "type")]
             type_: Type,
             content: String,
+            #[serde(default, deserialize_with = "deserialize_option_string_or_t")]
+            prio: Option<i64>,
         }
 
         ContentDeserializable::deserialize(deserializer)
-            .and_then(|c| Content::from(&c.type_, &c.content).map_err(D::Error::custom))
+            .map(|c| Content::from_api(&c.type_, &c.content, c.prio))
+    }
+}
+
+/// Serializes the content in the shape Porkbun uses, with its `type` and `content`, and
+/// its `prio` if it has a [priority](Content::priority).
+impl Serialize for Content {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct ContentSerializable {
+            #[serde(rename = "type")]
+            type_: Type,
+            content: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            prio: Option<i64>,
+        }
+
+        ContentSerializable {
+            type_: Type::from(self),
+            content: self.api_value(),
+            prio: self.api_prio(),
+        }
+        .serialize(serializer)
     }
 }
 
 /// A DNS record.
+///
+/// The `prio` that Porkbun sends is part of the [priority](Content::priority) of the
+/// content, for the types that have one.
 #[derive(Debug, Deserialize)]
+#[serde(from = "RecordDeserializable")]
 pub struct Record {
-    #[serde(deserialize_with = "deserialize_string_or_t")]
     pub id: i64,
     pub name: Box<Domain>,
-    #[serde(flatten)]
     pub content: Content,
-    #[serde(deserialize_with = "deserialize_string_or_t")]
     pub ttl: i64,
-    #[serde(deserialize_with = "deserialize_option_string_or_t")]
-    pub prio: Option<i64>,
     pub notes: Option<String>,
 }
 
+/// A DNS record as sent by the Porkbun API.
+#[derive(Deserialize)]
+struct RecordDeserializable {
+    #[serde(deserialize_with = "deserialize_string_or_t")]
+    id: i64,
+    name: Box<Domain>,
+    #[serde(rename = "type")]
+    type_: Type,
+    content: String,
+    #[serde(default = "default_ttl", deserialize_with = "deserialize_ttl")]
+    ttl: i64,
+    #[serde(default, deserialize_with = "deserialize_option_string_or_t")]
+    prio: Option<i64>,
+    notes: Option<String>,
+}
+
+impl From<RecordDeserializable> for Record {
+    fn from(value: RecordDeserializable) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            content: Content::from_api(&value.type_, &value.content, value.prio),
+            ttl: value.ttl,
+            notes: value.notes,
+        }
+    }
+}
+
+/// Serializes the record in the shape Porkbun sends it in, but with the numeric fields as
+/// numbers rather than strings.
+impl Serialize for Record {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct RecordSerializable<'a> {
+            id: i64,
+            name: &'a str,
+            #[serde(rename = "type")]
+            type_: Type,
+            content: String,
+            ttl: i64,
+            prio: Option<i64>,
+            notes: Option<&'a str>,
+        }
+
+        RecordSerializable {
+            id: self.id,
+            name: self.name.as_str(),
+            type_: Type::from(&self.content),
+            content: self.content.api_value(),
+            ttl: self.ttl,
+            prio: self.content.api_prio(),
+            notes: self.notes.as_deref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// A desired DNS record, to check the actual records against.
+#[derive(Debug)]
+pub struct RecordSpec {
+    pub name: Box<Domain>,
+    pub content: Content,
+    /// The desired TTL, or `None` if any TTL is acceptable.
+    pub ttl: Option<i64>,
+}
+
+/// Hosts that Porkbun points its own parking and URL-forwarding records at.
+const PORKBUN_MANAGED_TARGETS: [&str; 2] = ["pixie.porkbun.com", "uixie.porkbun.com"];
+
+/// Suffix of Porkbun's nameserver hosts.
+const PORKBUN_NAMESERVER_SUFFIX: &str = ".ns.porkbun.com";
+
+impl Record {
+    /// Returns whether the record looks like one that is managed by Porkbun itself.
+    ///
+    /// This is a heuristic. A record is considered managed by Porkbun if it is:
+    /// - an `ALIAS` or `CNAME` record pointing at Porkbun's parking or URL-forwarding
+    ///   hosts (`pixie.porkbun.com` and `uixie.porkbun.com`), or
+    /// - an `NS` record at the zone apex pointing at one of Porkbun's nameservers.
+    pub fn is_porkbun_managed(&self) -> bool {
+        fn normalize(target: &str) -> String {
+            target
+                .strip_suffix('.')
+                .unwrap_or(target)
+                .to_ascii_lowercase()
+        }
+
+        match &self.content {
+            Content::Alias(target) | Content::Cname(target) => {
+                PORKBUN_MANAGED_TARGETS.contains(&normalize(target).as_str())
+            }
+            Content::Ns(target) => {
+                self.name.prefix().is_none()
+                    && normalize(target).ends_with(PORKBUN_NAMESERVER_SUFFIX)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns a version token for the record, for detecting concurrent changes.
+    ///
+    /// This is a hash of the name, type, content and TTL of the record, so it changes
+    /// whenever one of them does, but not when only the notes do. Names and hostnames
+    /// are hashed case-insensitively, matching the equality of [`Content`]. Versions are
+    /// only meant to be compared with versions computed by the same build of a program.
+    pub fn version(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_hostname(self.name.as_str(), &mut hasher);
+        self.content.hash(&mut hasher);
+        self.ttl.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the content of the record.
+    pub fn content(&self) -> &Content {
+        &self.content
+    }
+
+    /// Returns whether the record matches the given spec.
+    ///
+    /// The names are compared case-insensitively and regardless of whether they are
+    /// fully qualified, and the type is compared as part of the content. A TTL in the
+    /// spec below the minimum TTL is compared as the minimum, as that's what Porkbun
+    /// would have stored.
+    pub fn matches(&self, spec: &RecordSpec) -> bool {
+        self.name
+            .not_fqdn()
+            .as_str()
+            .eq_ignore_ascii_case(spec.name.not_fqdn().as_str())
+            && self.content == spec.content
+            && spec
+                .ttl
+                .is_none_or(|ttl| ttl.max(crate::MIN_TTL) == self.ttl)
+    }
+}
+
+/// Removes the records that look like they are managed by Porkbun itself.
+///
+/// See [`Record::is_porkbun_managed`] for the heuristic used.
+pub fn user_records_only(mut records: Vec<Record>) -> Vec<Record> {
+    records.retain(|r| !r.is_porkbun_managed());
+    records
+}
+
+/// Sorts the records canonically and removes exact duplicates.
+///
+/// Records are ordered by name, type, priority and content, with ties broken by the
+/// remaining fields, so that the result doesn't depend on the order Porkbun returned them
+/// in. Hostnames in the content are ordered like they are compared, ignoring ASCII case
+/// and a trailing `.`. Records that only differ in their ID are considered duplicates,
+/// and only the one with the lowest ID is kept.
+pub fn sort_and_dedup(records: &mut Vec<Record>) {
+    /// Gets a key of the content that is equal for contents that are equal.
+    fn content_key(content: &Content) -> (Type, Option<u16>, (u16, u16), String) {
+        let hostname = |hostname: &str| hostname_key(hostname).to_ascii_lowercase();
+        let (weight_port, value) = match content {
+            Content::Cname(value) | Content::Alias(value) | Content::Ns(value) => {
+                ((0, 0), hostname(value))
+            }
+            Content::Mx { target, .. } => ((0, 0), hostname(target_str(target))),
+            Content::Srv {
+                weight,
+                port,
+                target,
+                ..
+            } => ((*weight, *port), hostname(target_str(target))),
+            _ => ((0, 0), content.value_to_string()),
+        };
+        (Type::from(content), content.priority(), weight_port, value)
+    }
+
+    records.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then_with(|| content_key(&a.content).cmp(&content_key(&b.content)))
+            .then_with(|| (a.ttl, &a.notes, a.id).cmp(&(b.ttl, &b.notes, b.id)))
+    });
+    records.dedup_by(|a, b| {
+        a.name == b.name && a.content == b.content && a.ttl == b.ttl && a.notes == b.notes
+    });
+}
+
 /// Helper type for deserializing a string or any T to a T.
 #[derive(Deserialize)]
 #[serde(untagged)]
@@ -228,8 +1203,24 @@ where
 
     let string_or_i64 = Option::<StringOr<T>>::deserialize(deserializer)?;
     Ok(match string_or_i64 {
+        // Porkbun sends empty strings for fields that don't apply.
+        Some(StringOr::<T>::String(s)) if s.trim().is_empty() => None,
         Some(StringOr::<T>::String(s)) => Some(s.parse().map_err(D::Error::custom)?),
         Some(StringOr::<T>::T(t)) => Some(t),
         None => None,
     })
 }
+
+/// Gets the TTL of a record that Porkbun didn't report one for, which is its default.
+fn default_ttl() -> i64 {
+    crate::MIN_TTL
+}
+
+/// Deserializes the TTL of a record, which Porkbun sends as an empty string for some
+/// record types.
+fn deserialize_ttl<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(deserialize_option_string_or_t(deserializer)?.unwrap_or_else(default_ttl))
+}
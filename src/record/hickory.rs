@@ -0,0 +1,113 @@
+//! Conversions between [`Content`] and hickory's [`RData`].
+//!
+//! Only available with the `hickory` feature.
+
+use hickory_proto::rr::{
+    Name, RData,
+    rdata::{A, AAAA, ANAME, CNAME, MX, NS, TXT},
+};
+
+use crate::RDataConversionError;
+use crate::record::{Content, parse_target, target_str};
+
+/// Converts a hostname from the content of a record to an absolute [`Name`].
+fn to_name(host: &str) -> Result<Name, RDataConversionError> {
+    let mut name = Name::from_ascii(host)?;
+    name.set_fqdn(true);
+    Ok(name)
+}
+
+/// Converts a [`Name`] to a hostname, without the trailing `.`.
+fn from_name(name: &Name) -> String {
+    let host = name.to_ascii();
+    match host.strip_suffix('.') {
+        Some(host) if !host.is_empty() => host.to_string(),
+        _ => host,
+    }
+}
+
+/// `ALIAS` content is converted to an `ANAME` record. `TXT` content is kept as a single
+/// character-string.
+///
+/// # Errors
+///
+/// Will return an error for content of types that aren't supported yet (`SRV`, `TLSA`,
+/// `CAA`, `HTTPS` and `SVCB`), for [raw](Content::Raw) content, or if a hostname is
+/// invalid.
+impl TryFrom<&Content> for RData {
+    type Error = RDataConversionError;
+
+    fn try_from(value: &Content) -> Result<Self, Self::Error> {
+        Ok(match value {
+            Content::A(addr) => RData::A(A(*addr)),
+            Content::Aaaa(addr) => RData::AAAA(AAAA(*addr)),
+            Content::Mx { priority, target } => {
+                RData::MX(MX::new(*priority, to_name(target_str(target))?))
+            }
+            Content::Cname(host) => RData::CNAME(CNAME(to_name(host)?)),
+            Content::Alias(host) => RData::ANAME(ANAME(to_name(host)?)),
+            Content::Ns(host) => RData::NS(NS(to_name(host)?)),
+            Content::Txt(value) => RData::TXT(TXT::new(vec![value.clone()])),
+            Content::Raw { value, .. } => {
+                return Err(RDataConversionError::InvalidContent(value.clone()));
+            }
+            _ => {
+                return Err(RDataConversionError::UnsupportedType(
+                    value.type_as_str().to_string(),
+                ));
+            }
+        })
+    }
+}
+
+impl TryFrom<Content> for RData {
+    type Error = RDataConversionError;
+
+    fn try_from(value: Content) -> Result<Self, Self::Error> {
+        RData::try_from(&value)
+    }
+}
+
+/// `ANAME` records are converted to `ALIAS` content. The character-strings of `TXT`
+/// records are concatenated, as is usual for e.g. SPF and DKIM records.
+///
+/// # Errors
+///
+/// Will return an error for records of types that have no [`Content`] equivalent, or
+/// that aren't supported yet, or if the target of an `MX` record isn't a valid domain.
+impl TryFrom<&RData> for Content {
+    type Error = RDataConversionError;
+
+    fn try_from(value: &RData) -> Result<Self, Self::Error> {
+        Ok(match value {
+            RData::A(A(addr)) => Content::A(*addr),
+            RData::AAAA(AAAA(addr)) => Content::Aaaa(*addr),
+            RData::MX(mx) => Content::Mx {
+                priority: mx.preference(),
+                target: parse_target(&from_name(mx.exchange()))?,
+            },
+            RData::CNAME(CNAME(name)) => Content::Cname(from_name(name)),
+            RData::ANAME(ANAME(name)) => Content::Alias(from_name(name)),
+            RData::NS(NS(name)) => Content::Ns(from_name(name)),
+            RData::TXT(txt) => Content::Txt(
+                txt.txt_data()
+                    .iter()
+                    .map(|s| String::from_utf8_lossy(s))
+                    .collect(),
+            ),
+            _ => {
+                return Err(RDataConversionError::UnsupportedType(
+                    value.record_type().to_string(),
+                ));
+            }
+        })
+    }
+}
+
+impl TryFrom<RData> for Content {
+    type Error = RDataConversionError;
+
+    fn try_from(value: RData) -> Result<Self, Self::Error> {
+        Content::try_from(&value)
+    }
+}
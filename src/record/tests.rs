@@ -0,0 +1,679 @@
+use super::*;
+
+#[test]
+fn ns_content() {
+    assert_eq!(
+        Content::from(&Type::Ns, "curitiba.ns.porkbun.com").unwrap(),
+        Content::Ns("curitiba.ns.porkbun.com".to_string())
+    );
+    assert!(matches!(
+        Content::from(&Type::Ns, "not a nameserver"),
+        Err(ContentCreationError::Domain(_))
+    ));
+    assert!(matches!(
+        Content::from(&Type::Ns, ""),
+        Err(ContentCreationError::Domain(_))
+    ));
+}
+
+#[test]
+fn filter_porkbun_managed() {
+    let records: Vec<Record> = serde_json::from_str(
+        r#"[
+            {"id":"1","name":"example.com","type":"ALIAS","content":"pixie.porkbun.com","ttl":"600","prio":null,"notes":null},
+            {"id":"2","name":"*.example.com","type":"CNAME","content":"pixie.porkbun.com","ttl":"600","prio":null,"notes":null},
+            {"id":"3","name":"example.com","type":"NS","content":"curitiba.ns.porkbun.com","ttl":"86400","prio":null,"notes":null},
+            {"id":"4","name":"www.example.com","type":"A","content":"1.2.3.4","ttl":"600","prio":"0","notes":""},
+            {"id":"5","name":"blog.example.com","type":"CNAME","content":"example.com","ttl":"600","prio":null,"notes":null},
+            {"id":"6","name":"sub.example.com","type":"NS","content":"ns1.example.net","ttl":"600","prio":null,"notes":null}
+        ]"#,
+    )
+    .unwrap();
+
+    let ids: Vec<i64> = user_records_only(records).iter().map(|r| r.id).collect();
+    assert_eq!(ids, [4, 5, 6]);
+}
+
+#[test]
+fn content_from_str() {
+    assert_eq!(
+        "A:1.2.3.4".parse::<Content>().unwrap(),
+        Content::A(Ipv4Addr::new(1, 2, 3, 4))
+    );
+    assert_eq!(
+        "aaaa:::1".parse::<Content>().unwrap(),
+        Content::Aaaa(Ipv6Addr::LOCALHOST)
+    );
+    assert_eq!(
+        "TXT:hello:world".parse::<Content>().unwrap(),
+        Content::Txt("hello:world".to_string())
+    );
+    assert!(matches!(
+        "BOGUS:value".parse::<Content>(),
+        Err(ContentCreationError::UnknownType(t)) if t == "BOGUS"
+    ));
+    assert!(matches!(
+        "1.2.3.4".parse::<Content>(),
+        Err(ContentCreationError::MissingType(_))
+    ));
+    assert!(matches!(
+        "A:not-an-ip".parse::<Content>(),
+        Err(ContentCreationError::AddrParse(_))
+    ));
+}
+
+#[test]
+fn spf_and_dkim() {
+    let spf = Content::spf(&[
+        "a",
+        "mx",
+        "include:_spf.google.com",
+        "include:spf.protection.outlook.com",
+        "-all",
+    ]);
+    assert_eq!(
+        spf,
+        Content::Txt(
+            "v=spf1 a mx include:_spf.google.com include:spf.protection.outlook.com -all"
+                .to_string()
+        )
+    );
+    assert!(spf.is_spf());
+    assert!(!spf.is_dkim());
+    assert!(!Content::Txt("v=spf10".to_string()).is_spf());
+    assert!(!Content::Cname("v=spf1".to_string()).is_spf());
+
+    let (prefix, short) = Content::dkim("s1", "MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQ");
+    assert_eq!(prefix, "s1._domainkey");
+    assert_eq!(
+        short,
+        Content::Txt("v=DKIM1; k=rsa; p=MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQ".to_string())
+    );
+    assert!(short.is_dkim());
+    assert!(!short.is_spf());
+
+    // A 2048-bit key is around 400 characters long, so it needs splitting.
+    let key = "A".repeat(392);
+    let (_, long) = Content::dkim("s1", &key);
+    let Content::Txt(value) = &long else {
+        panic!("expected TXT content");
+    };
+    assert!(value.starts_with('"') && value.ends_with('"'));
+    let chunks: Vec<&str> = value.trim_matches('"').split("\" \"").collect();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].len(), 255);
+    assert_eq!(chunks.concat(), format!("v=DKIM1; k=rsa; p={key}"));
+    assert!(long.is_dkim());
+
+    // Characters are never split across strings.
+    let mechanism = format!("exp={}", "é".repeat(200));
+    let Content::Txt(value) = Content::spf(&[&mechanism, "-all"]) else {
+        panic!("expected TXT content");
+    };
+    assert!(!value.contains('\u{fffd}'));
+    assert_eq!(
+        value.trim_matches('"').split("\" \"").collect::<String>(),
+        format!("v=spf1 {mechanism} -all")
+    );
+}
+
+#[test]
+fn content_eq_and_hash() {
+    use std::collections::HashSet;
+
+    let mut set = HashSet::new();
+    assert!(set.insert(Content::Cname("Example.COM".to_string())));
+    assert!(!set.insert(Content::Cname("example.com.".to_string())));
+    assert!(set.insert(Content::Alias("example.com".to_string())));
+    assert!(set.insert(Content::Txt("Hello".to_string())));
+    assert!(set.insert(Content::Txt("hello".to_string())));
+    assert!(set.insert(Content::from(&Type::Aaaa, "2001:db8::1").unwrap()));
+    assert!(!set.insert(Content::from(&Type::Aaaa, "2001:0db8:0:0::0001").unwrap()));
+    assert_eq!(set.len(), 5);
+
+    assert_eq!(
+        Content::Ns("NS1.example.net.".to_string()),
+        Content::Ns("ns1.example.net".to_string())
+    );
+    assert_ne!(
+        Content::Cname("example.com".to_string()),
+        Content::Ns("example.com".to_string())
+    );
+}
+
+#[test]
+fn mx_priority() {
+    let mx = Content::from(&Type::Mx, "10 mail.example.com").unwrap();
+    assert_eq!(
+        mx,
+        Content::Mx {
+            priority: 10,
+            target: Some(Domain::parse("mail.example.com").unwrap())
+        }
+    );
+    assert_eq!(mx.priority(), Some(10));
+    assert_eq!(mx.value_to_string(), "10 mail.example.com");
+    assert_eq!(mx.api_value(), "mail.example.com");
+    assert!(matches!(
+        Content::from(&Type::Mx, "mail.example.com"),
+        Err(ContentCreationError::InvalidMx(_))
+    ));
+    assert!(matches!(
+        Content::from(&Type::Mx, "high mail.example.com"),
+        Err(ContentCreationError::ParseInt(_))
+    ));
+
+    let record: Record = serde_json::from_str(
+        r#"{"id":"1","name":"example.com","type":"MX","content":"mail.example.com","ttl":"600","prio":"10","notes":null}"#,
+    )
+    .unwrap();
+    assert_eq!(record.content, mx);
+    assert_eq!(Type::from(&record.content), Type::Mx);
+}
+
+#[test]
+fn sorting_and_deduplication() {
+    let json = [
+        r#"{"id":"1","name":"www.example.com","type":"A","content":"2.2.2.2","ttl":"600","prio":null,"notes":null}"#,
+        r#"{"id":"2","name":"example.com","type":"TXT","content":"b","ttl":"600","prio":null,"notes":null}"#,
+        r#"{"id":"3","name":"example.com","type":"MX","content":"mail.example.com","ttl":"600","prio":"20","notes":null}"#,
+        r#"{"id":"4","name":"www.example.com","type":"A","content":"10.0.0.1","ttl":"600","prio":null,"notes":null}"#,
+        r#"{"id":"5","name":"example.com","type":"MX","content":"mail.example.com","ttl":"600","prio":"10","notes":null}"#,
+        r#"{"id":"6","name":"www.example.com","type":"A","content":"2.2.2.2","ttl":"600","prio":null,"notes":null}"#,
+        r#"{"id":"7","name":"example.com","type":"TXT","content":"a","ttl":"600","prio":null,"notes":null}"#,
+    ];
+    let parse = |order: &[usize]| -> Vec<Record> {
+        order
+            .iter()
+            .map(|&i| serde_json::from_str(json[i]).unwrap())
+            .collect()
+    };
+
+    let mut forward = parse(&[0, 1, 2, 3, 4, 5, 6]);
+    let mut shuffled = parse(&[5, 3, 6, 0, 2, 4, 1]);
+    sort_and_dedup(&mut forward);
+    sort_and_dedup(&mut shuffled);
+
+    let ids = |records: &[Record]| records.iter().map(|r| r.id).collect::<Vec<_>>();
+    assert_eq!(ids(&forward), [5, 3, 7, 2, 4, 1]);
+    assert_eq!(ids(&shuffled), ids(&forward));
+}
+
+#[test]
+fn deduplication_of_hostnames() {
+    let record = |id: i64, content: &str, ttl: i64| Record {
+        id,
+        name: "example.com".parse().unwrap(),
+        content: Content::from(&Type::Cname, content).unwrap(),
+        ttl,
+        notes: None,
+    };
+
+    let mut records = vec![
+        record(5, "A.example.com", 300),
+        record(3, "A.example.com", 600),
+        record(1, "a.example.com", 300),
+        record(4, "mail.example.com.", 300),
+        record(2, "mail.example.com", 300),
+    ];
+    sort_and_dedup(&mut records);
+    let ids: Vec<_> = records.iter().map(|r| r.id).collect();
+    assert_eq!(ids, [1, 3, 2]);
+}
+
+#[test]
+fn address_families() {
+    assert!(matches!(
+        "AAAA:::ffff:192.0.2.1".parse::<Content>(),
+        Err(ContentCreationError::MappedAddress(c)) if c == "::ffff:192.0.2.1"
+    ));
+    assert!(matches!(
+        "A:2001:db8::1".parse::<Content>(),
+        Err(ContentCreationError::AddressFamily { type_: Type::A, .. })
+    ));
+    assert!(matches!(
+        "AAAA:192.0.2.1".parse::<Content>(),
+        Err(ContentCreationError::AddressFamily {
+            type_: Type::Aaaa,
+            ..
+        })
+    ));
+    assert_eq!(
+        "AAAA:2001:db8::1".parse::<Content>().unwrap(),
+        Content::Aaaa("2001:db8::1".parse().unwrap())
+    );
+
+    let mapped: IpAddr = "::ffff:192.0.2.1".parse().unwrap();
+    let content: Content = mapped.into();
+    assert_eq!(content, Content::A(Ipv4Addr::new(192, 0, 2, 1)));
+}
+
+#[cfg(feature = "hickory")]
+#[test]
+fn rdata_conversion() {
+    use hickory_proto::rr::{RData, rdata};
+
+    let a = Content::A(Ipv4Addr::new(192, 0, 2, 1));
+    let rdata = RData::try_from(&a).unwrap();
+    assert_eq!(rdata, RData::A(rdata::A(Ipv4Addr::new(192, 0, 2, 1))));
+    assert_eq!(Content::try_from(rdata).unwrap(), a);
+
+    let mx = Content::Mx {
+        priority: 10,
+        target: Some(Domain::parse("mail.example.com").unwrap()),
+    };
+    let rdata = RData::try_from(&mx).unwrap();
+    match &rdata {
+        RData::MX(record) => {
+            assert_eq!(record.preference(), 10);
+            assert_eq!(record.exchange().to_ascii(), "mail.example.com.");
+        }
+        other => panic!("expected an MX record, got {other:?}"),
+    }
+    match Content::try_from(rdata).unwrap() {
+        Content::Mx { priority, target } => {
+            assert_eq!(priority, 10);
+            assert_eq!(target.unwrap().as_str(), "mail.example.com");
+        }
+        other => panic!("expected MX content, got {other:?}"),
+    }
+
+    assert!(matches!(
+        RData::try_from(Content::from(&Type::Caa, "0 issue \"letsencrypt.org\"").unwrap()),
+        Err(crate::RDataConversionError::UnsupportedType(t)) if t == "CAA"
+    ));
+}
+
+#[test]
+fn txt_byte_len() {
+    // 127 two-byte characters and a single-byte one: 128 characters, 255 bytes.
+    let max = Content::Txt(format!("{}a", "é".repeat(127)));
+    assert_eq!(max.txt_byte_len(), Some(255));
+    assert!(!max.txt_needs_splitting());
+
+    let over = Content::Txt("é".repeat(128));
+    assert_eq!(over.txt_byte_len(), Some(256));
+    assert!(over.txt_needs_splitting());
+
+    let a = Content::A(Ipv4Addr::LOCALHOST);
+    assert_eq!(a.txt_byte_len(), None);
+    assert!(!a.txt_needs_splitting());
+}
+
+#[test]
+fn caa_content() {
+    for content in [
+        "0 issue \"letsencrypt.org\"",
+        "0 issuewild \"pki.goog; cansignhttpexchanges=yes\"",
+        "0 issue \";\"",
+        "0 iodef \"mailto:sec@example.com\"",
+        "128 iodef \"https://example.com/caa\"",
+        "0 tbs \"anything goes\"",
+    ] {
+        assert!(Content::from(&Type::Caa, content).is_ok(), "{content}");
+    }
+
+    assert!(matches!(
+        Content::from(&Type::Caa, "0 iodef \"sec@example.com\""),
+        Err(ContentCreationError::InvalidCaaIodef(v)) if v == "sec@example.com"
+    ));
+    assert!(matches!(
+        Content::from(&Type::Caa, "0 iodef \"ftp://example.com\""),
+        Err(ContentCreationError::InvalidCaaIodef(_))
+    ));
+    assert!(matches!(
+        Content::from(&Type::Caa, "0 issuewild \"not a ca; policy=ev\""),
+        Err(ContentCreationError::InvalidCaaIssuer(_))
+    ));
+    assert!(matches!(
+        Content::from(&Type::Caa, "0 issue"),
+        Err(ContentCreationError::InvalidCaa(_))
+    ));
+    assert!(matches!(
+        Content::from(&Type::Caa, "0 issue \"letsencrypt.org"),
+        Err(ContentCreationError::InvalidCaa(_))
+    ));
+    assert!(matches!(
+        Content::from(&Type::Caa, "256 issue \"letsencrypt.org\""),
+        Err(ContentCreationError::ParseInt(_))
+    ));
+}
+
+#[test]
+fn structured_content() {
+    let srv = Content::from(&Type::Srv, "10 5 5060 SIP.example.com.").unwrap();
+    let Content::Srv {
+        priority,
+        weight,
+        port,
+        target: Some(target),
+    } = &srv
+    else {
+        panic!("expected SRV content with a target, got {srv:?}");
+    };
+    assert_eq!((*priority, *weight, *port), (10, 5, 5060));
+    assert_eq!(target.as_str(), "SIP.example.com.");
+    assert_eq!(srv.priority(), Some(10));
+    assert_eq!(srv.value_to_string(), "10 5 5060 SIP.example.com.");
+    assert_eq!(srv.api_value(), "5 5060 SIP.example.com.");
+    assert_eq!(
+        srv,
+        Content::from(&Type::Srv, "10 5 5060 sip.example.com").unwrap()
+    );
+
+    // Porkbun sends the priority separately from the rest of the content.
+    let record: Record = serde_json::from_str(
+        r#"{"id":"1","name":"_sip._tcp.example.com","type":"SRV","content":"5 5060 SIP.example.com.","ttl":"600","prio":"10","notes":null}"#,
+    )
+    .unwrap();
+    assert_eq!(record.content, srv);
+
+    let null_mx = Content::from(&Type::Mx, "0 .").unwrap();
+    assert!(matches!(null_mx, Content::Mx { target: None, .. }));
+    assert_eq!(null_mx.value_to_string(), "0 .");
+
+    let caa = Content::from(&Type::Caa, "128 Issue letsencrypt.org").unwrap();
+    assert_eq!(
+        caa,
+        Content::Caa {
+            flags: 128,
+            tag: CaaTag::Issue,
+            value: "letsencrypt.org".to_string(),
+        }
+    );
+    assert_eq!(caa.value_to_string(), "128 issue \"letsencrypt.org\"");
+    assert_eq!(
+        "tbs".parse::<CaaTag>().unwrap(),
+        CaaTag::Other("tbs".to_string())
+    );
+    assert!(matches!(
+        Content::from(&Type::Caa, "0 is-sue \"letsencrypt.org\""),
+        Err(ContentCreationError::InvalidCaaTag(t)) if t == "is-sue"
+    ));
+
+    for (type_, content) in [
+        (Type::Mx, "10 mail.example.com"),
+        (Type::Srv, "0 0 0 ."),
+        (Type::Caa, "0 iodef \"mailto:sec@example.com\""),
+    ] {
+        let parsed = Content::from(&type_, content).unwrap();
+        assert_eq!(
+            Content::from_api(&type_, &parsed.api_value(), parsed.api_prio()),
+            parsed
+        );
+    }
+}
+
+#[test]
+fn value_round_trip() {
+    for type_ in Type::all() {
+        let value = match type_ {
+            Type::A => "192.0.2.1",
+            Type::Mx => "10 mail.example.com",
+            Type::Cname => "target.example.com",
+            Type::Alias => "pixie.porkbun.com",
+            Type::Txt => "v=spf1 -all",
+            Type::Ns => "curitiba.ns.porkbun.com",
+            Type::Aaaa => "2001:db8::1",
+            Type::Srv => "10 5 5060 sip.example.com",
+            Type::Tlsa => "3 1 1 0123456789abcdef",
+            Type::Caa => "0 issue \"letsencrypt.org\"",
+            Type::Https => "1 . alpn=h2,h3",
+            Type::Svcb => "0 svc.example.com",
+        };
+        let content = Content::from(type_, value).unwrap();
+        assert_eq!(content.value_to_string(), value, "{type_:?}");
+        assert_eq!(
+            Content::from(type_, &content.value_to_string()).unwrap(),
+            content
+        );
+    }
+
+    let raw = Content::from(&Type::Srv, "10 sip.example.com").unwrap();
+    assert_eq!(
+        Content::from(&Type::Srv, &raw.value_to_string()).unwrap(),
+        raw
+    );
+}
+
+#[test]
+fn strict_content() {
+    for (type_, content) in [
+        (Type::Cname, "target.example.com."),
+        (Type::Mx, "10 mail.example.com"),
+        (Type::Srv, "10 5 5060 sip.example.com"),
+        (Type::Srv, "0 0 0 ."),
+        (Type::Tlsa, "3 1 1 0123456789abcdef 0123456789ABCDEF"),
+        (Type::Https, "1 . alpn=h2,h3"),
+        (Type::Svcb, "0 svc.example.com"),
+        (Type::Txt, "anything goes"),
+    ] {
+        let strict = Content::from_strict(&type_, content).unwrap();
+        assert_eq!(strict, Content::from(&type_, content).unwrap());
+    }
+
+    // A malformed SRV record is kept as raw content by the lenient constructor.
+    assert_eq!(
+        Content::from(&Type::Srv, "10 sip.example.com").unwrap(),
+        Content::Raw {
+            type_: Type::Srv,
+            value: "10 sip.example.com".to_string(),
+            priority: None,
+        }
+    );
+    assert!(matches!(
+        Content::from_strict(&Type::Srv, "10 sip.example.com"),
+        Err(ContentCreationError::InvalidSrv(_))
+    ));
+    assert!(matches!(
+        Content::from_strict(&Type::Srv, "10 5 70000 sip.example.com"),
+        Err(ContentCreationError::ParseInt(_))
+    ));
+    assert!(matches!(
+        Content::from_strict(&Type::Cname, "not a host"),
+        Err(ContentCreationError::Domain(_))
+    ));
+    assert!(matches!(
+        Content::from_strict(&Type::Tlsa, "4 1 1 abcd"),
+        Err(ContentCreationError::InvalidTlsa(_))
+    ));
+    assert!(matches!(
+        Content::from_strict(&Type::Tlsa, "3 1 1 abc"),
+        Err(ContentCreationError::InvalidTlsa(_))
+    ));
+    assert!(matches!(
+        Content::from_strict(&Type::Https, "1"),
+        Err(ContentCreationError::InvalidSvcb(_))
+    ));
+}
+
+#[test]
+fn all_types() {
+    assert_eq!(Type::all().len(), 12);
+    assert_eq!(Type::all_str().len(), Type::all().len());
+    for (type_, name) in Type::all().iter().zip(Type::all_str()) {
+        assert_eq!(type_.as_str(), *name);
+        assert_eq!(name.parse::<Type>().unwrap(), *type_);
+        assert_eq!(name.to_lowercase().parse::<Type>().unwrap(), *type_);
+    }
+    assert_eq!(Type::all()[0], Type::A);
+    assert_eq!(Type::all_str()[1], "MX");
+}
+
+#[test]
+fn empty_content() {
+    for content in ["", "   ", "\"\"", " \" \" "] {
+        assert!(
+            matches!(
+                Content::from(&Type::Txt, content),
+                Err(ContentCreationError::EmptyTxt)
+            ),
+            "{content:?}"
+        );
+    }
+    assert!(Content::from(&Type::Txt, "\"v=spf1 -all\"").is_ok());
+
+    for type_ in [
+        Type::Cname,
+        Type::Alias,
+        Type::Srv,
+        Type::Tlsa,
+        Type::Https,
+        Type::Svcb,
+    ] {
+        assert!(matches!(
+            Content::from(&type_, " "),
+            Err(ContentCreationError::EmptyContent { type_: t }) if t == type_
+        ));
+    }
+    assert!(Content::from(&Type::Ns, "").is_err());
+    assert!(Content::from(&Type::Mx, "").is_err());
+    assert!(Content::from(&Type::Caa, "").is_err());
+    assert!("TXT:".parse::<Content>().is_err());
+}
+
+#[test]
+fn empty_numeric_fields() {
+    let record: Record = serde_json::from_str(
+        r#"{"id":"1","name":"example.com","type":"ALIAS","content":"pixie.porkbun.com","ttl":"","prio":"","notes":""}"#,
+    )
+    .unwrap();
+    assert_eq!(record.ttl, 600);
+    assert_eq!(record.content.priority(), None);
+
+    let record: Record = serde_json::from_str(
+        r#"{"id":"2","name":"example.com","type":"A","content":"192.0.2.1","ttl":null,"notes":null}"#,
+    )
+    .unwrap();
+    assert_eq!(record.ttl, 600);
+    assert_eq!(record.content.priority(), None);
+
+    let record: Record = serde_json::from_str(
+        r#"{"id":"3","name":"example.com","type":"MX","content":"mail.example.com","ttl":" ","prio":"10","notes":null}"#,
+    )
+    .unwrap();
+    assert_eq!(record.ttl, 600);
+    assert_eq!(record.content.priority(), Some(10));
+
+    assert!(
+        serde_json::from_str::<Record>(
+            r#"{"id":"","name":"example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":"0","notes":null}"#,
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn invalid_api_content() {
+    let records: Vec<Record> = serde_json::from_str(
+        r#"[
+            {"id":"1","name":"example.com","type":"TXT","content":"","ttl":"600","prio":"0","notes":null},
+            {"id":"2","name":"example.com","type":"NS","content":"ns1.example.invalid","ttl":"600","prio":null,"notes":null},
+            {"id":"3","name":"example.com","type":"MX","content":"mail.example.com","ttl":"600","prio":"70000","notes":null},
+            {"id":"4","name":"example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":"0","notes":null}
+        ]"#,
+    )
+    .unwrap();
+    let contents: Vec<_> = records.into_iter().map(|r| r.content).collect();
+    assert_eq!(
+        contents,
+        [
+            Content::Raw {
+                type_: Type::Txt,
+                value: String::new(),
+                priority: None,
+            },
+            Content::Raw {
+                type_: Type::Ns,
+                value: "ns1.example.invalid".to_string(),
+                priority: None,
+            },
+            Content::Raw {
+                type_: Type::Mx,
+                value: "mail.example.com".to_string(),
+                priority: Some(70000),
+            },
+            Content::A(Ipv4Addr::new(192, 0, 2, 1)),
+        ]
+    );
+    assert_eq!(contents[1].type_as_str(), "NS");
+    assert_eq!(contents[2].priority(), None);
+}
+
+#[test]
+fn record_matches() {
+    let record = Record {
+        id: 1,
+        name: "www.example.com".parse().unwrap(),
+        content: Content::A("192.0.2.1".parse().unwrap()),
+        ttl: 600,
+        notes: None,
+    };
+    assert_eq!(record.content(), &record.content);
+
+    let mut spec = RecordSpec {
+        name: "WWW.example.com.".parse().unwrap(),
+        content: Content::A("192.0.2.1".parse().unwrap()),
+        ttl: Some(300),
+    };
+    assert!(record.matches(&spec));
+    spec.ttl = None;
+    assert!(record.matches(&spec));
+
+    spec.ttl = Some(3600);
+    assert!(!record.matches(&spec));
+    spec.ttl = None;
+    spec.content = Content::Aaaa("2001:db8::1".parse().unwrap());
+    assert!(!record.matches(&spec));
+    spec.content = record.content.clone();
+    spec.name = "example.com".parse().unwrap();
+    assert!(!record.matches(&spec));
+}
+
+#[test]
+fn serialize_round_trip() {
+    let response = r#"[
+        {"id":"1","name":"example.com","type":"A","content":"192.0.2.1","ttl":"600","prio":"0","notes":""},
+        {"id":"2","name":"example.com","type":"MX","content":"mail.example.com","ttl":"3600","prio":"10","notes":null},
+        {"id":"3","name":"_dmarc.example.com","type":"TXT","content":"v=DMARC1; p=none","ttl":"600","prio":null,"notes":"dmarc"}
+    ]"#;
+    let records: Vec<Record> = serde_json::from_str(response).unwrap();
+    let serialized = serde_json::to_value(&records).unwrap();
+    assert_eq!(
+        serialized[1],
+        serde_json::json!({
+            "id": 2,
+            "name": "example.com",
+            "type": "MX",
+            "content": "mail.example.com",
+            "ttl": 3600,
+            "prio": 10,
+            "notes": null,
+        })
+    );
+
+    let read_back: Vec<Record> = serde_json::from_value(serialized.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&read_back).unwrap(), serialized);
+    for (record, read_back) in records.iter().zip(&read_back) {
+        assert_eq!(record.id, read_back.id);
+        assert_eq!(record.name, read_back.name);
+        assert_eq!(record.content, read_back.content);
+        assert_eq!(record.ttl, read_back.ttl);
+        assert_eq!(record.notes, read_back.notes);
+    }
+
+    let content = Content::from(&Type::Mx, "10 mail.example.com").unwrap();
+    let serialized = serde_json::to_value(&content).unwrap();
+    assert_eq!(
+        serialized,
+        serde_json::json!({"type": "MX", "content": "mail.example.com", "prio": 10})
+    );
+    assert_eq!(
+        serde_json::from_value::<Content>(serialized).unwrap(),
+        content
+    );
+    assert_eq!(
+        serde_json::to_value(Content::Txt("hello".to_string())).unwrap(),
+        serde_json::json!({"type": "TXT", "content": "hello"})
+    );
+}
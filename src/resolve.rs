@@ -0,0 +1,132 @@
+//! Resolving DNS records, to check whether changes made through the API have propagated.
+//!
+//! Only available with the `resolve` feature.
+
+use std::{str::FromStr, time::Duration};
+
+use hickory_resolver::{
+    Resolver, TokioAsyncResolver,
+    config::{ResolverConfig, ResolverOpts},
+    error::{ResolveError, ResolveErrorKind},
+    lookup::Lookup,
+    proto::rr::RecordType,
+    system_conf,
+};
+
+use crate::ClientError;
+use crate::domain::Domain;
+use crate::record::{Content, Type};
+
+/// The delay before polling a resolver again when waiting for a record to resolve.
+///
+/// This is doubled after every poll, up to [`MAX_POLL_INTERVAL`].
+pub(crate) const INITIAL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The maximum delay between polls of a resolver.
+pub(crate) const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A DNS resolver, for use with [`Client::wait_until_resolves_with`].
+///
+/// [`Client::wait_until_resolves_with`]: crate::Client::wait_until_resolves_with
+pub trait Resolve {
+    /// Looks up the records of the given type at the given domain.
+    ///
+    /// A domain without records of the type resolves to no contents, rather than an
+    /// error.
+    fn resolve(
+        &self,
+        domain: &Domain,
+        type_: Type,
+    ) -> impl Future<Output = Result<Vec<Content>, ClientError>> + Send;
+}
+
+/// A blocking DNS resolver, for use with [`Client::wait_until_resolves_with`].
+///
+/// [`Client::wait_until_resolves_with`]: crate::blocking::Client::wait_until_resolves_with
+pub trait BlockingResolve {
+    /// Looks up the records of the given type at the given domain.
+    ///
+    /// A domain without records of the type resolves to no contents, rather than an
+    /// error.
+    fn resolve(&self, domain: &Domain, type_: Type) -> Result<Vec<Content>, ClientError>;
+}
+
+impl Resolve for TokioAsyncResolver {
+    async fn resolve(&self, domain: &Domain, type_: Type) -> Result<Vec<Content>, ClientError> {
+        let record_type = record_type(type_)?;
+        let lookup = self.lookup(query_name(domain), record_type).await;
+        contents(record_type, lookup)
+    }
+}
+
+impl BlockingResolve for Resolver {
+    fn resolve(&self, domain: &Domain, type_: Type) -> Result<Vec<Content>, ClientError> {
+        let record_type = record_type(type_)?;
+        let lookup = self.lookup(query_name(domain), record_type);
+        contents(record_type, lookup)
+    }
+}
+
+/// Gets the configuration of the system resolver, with caching disabled.
+///
+/// Cached answers would hide a change from every subsequent poll.
+fn system_config() -> Result<(ResolverConfig, ResolverOpts), ResolveError> {
+    let (config, mut opts) = system_conf::read_system_conf()?;
+    opts.positive_max_ttl = Some(Duration::ZERO);
+    opts.negative_max_ttl = Some(Duration::ZERO);
+    Ok((config, opts))
+}
+
+/// Creates a resolver using the system configuration.
+pub(crate) fn system_resolver() -> Result<TokioAsyncResolver, ClientError> {
+    let (config, opts) = system_config()?;
+    Ok(TokioAsyncResolver::tokio(config, opts))
+}
+
+/// Creates a blocking resolver using the system configuration.
+pub(crate) fn blocking_system_resolver() -> Result<Resolver, ClientError> {
+    let (config, opts) = system_config()?;
+    Ok(Resolver::new(config, opts).map_err(ResolveError::from)?)
+}
+
+/// Checks that records of the given type can be resolved.
+///
+/// `ALIAS` records are flattened by Porkbun, so they can't be looked up.
+pub(crate) fn check_resolvable(type_: Type) -> Result<(), ClientError> {
+    if type_ == Type::Alias {
+        return Err(ClientError::Validation(
+            "ALIAS records are flattened by Porkbun and can't be resolved".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Gets the DNS record type to look up for the given type.
+fn record_type(type_: Type) -> Result<RecordType, ClientError> {
+    check_resolvable(type_)?;
+    RecordType::from_str(type_.as_str()).map_err(|e| ClientError::Validation(e.to_string()))
+}
+
+/// Gets the fully-qualified name to look up, so that search domains aren't applied.
+fn query_name(domain: &Domain) -> String {
+    format!("{}.", domain.not_fqdn())
+}
+
+/// Converts the result of a lookup to the contents of the records of the given type.
+///
+/// Records of other types, such as the `CNAME`s followed to get to the answer, are
+/// skipped, as are records that can't be represented as a [`Content`].
+fn contents(
+    record_type: RecordType,
+    lookup: Result<Lookup, ResolveError>,
+) -> Result<Vec<Content>, ClientError> {
+    match lookup {
+        Ok(lookup) => Ok(lookup
+            .iter()
+            .filter(|rdata| rdata.record_type() == record_type)
+            .filter_map(|rdata| Content::try_from(rdata).ok())
+            .collect()),
+        Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
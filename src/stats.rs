@@ -0,0 +1,80 @@
+//! Optional metrics about the requests made by the clients.
+//!
+//! When the `metrics` feature is disabled, these functions do nothing.
+
+use url::Url;
+
+use crate::errors::ClientError;
+
+/// Gets a low-cardinality label for the API call of the given url.
+///
+/// This is at most the first two path segments after the endpoint, e.g. `dns/create`,
+/// so that domain names and record IDs don't end up in the label.
+#[cfg(feature = "metrics")]
+fn call_label(endpoint: &Url, url: &Url) -> String {
+    endpoint
+        .make_relative(url)
+        .unwrap_or_default()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .take(2)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Gets the `kind` label of the given error.
+#[cfg(feature = "metrics")]
+fn error_kind(err: &ClientError) -> &'static str {
+    match err {
+        ClientError::InvalidCredentials => "auth",
+        ClientError::Porkbun(err) if err.is_auth_error() => "auth",
+        ClientError::Porkbun(_) => "api",
+        ClientError::QuotaExceeded => "quota",
+        ClientError::Reqwest(err) if err.is_decode() => "json",
+        ClientError::Json(_) => "json",
+        _ => "http",
+    }
+}
+
+/// Records a finished call to the given url.
+///
+/// Every call counts towards `hamsando_requests_total`, with an `outcome` of `success` or
+/// `error`. Failed calls also count towards `hamsando_errors_total`, whose `kind` is one of
+/// `http`, `api`, `json`, `quota` or `auth`.
+#[cfg(feature = "metrics")]
+pub(crate) fn record_result<T>(endpoint: &Url, url: &Url, result: &Result<T, ClientError>) {
+    let label = call_label(endpoint, url);
+    let outcome = match result {
+        Ok(_) => "success",
+        Err(err) => {
+            metrics::counter!(
+                "hamsando_errors_total",
+                "endpoint" => label.clone(),
+                "kind" => error_kind(err),
+            )
+            .increment(1);
+            "error"
+        }
+    };
+    metrics::counter!(
+        "hamsando_requests_total",
+        "endpoint" => label,
+        "outcome" => outcome,
+    )
+    .increment(1);
+}
+
+/// Records that a rate-limited request to the given url is being retried.
+#[cfg(feature = "metrics")]
+pub(crate) fn record_retry(endpoint: &Url, url: &Url) {
+    metrics::counter!("hamsando_retries_total", "endpoint" => call_label(endpoint, url))
+        .increment(1);
+}
+
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn record_result<T>(_endpoint: &Url, _url: &Url, _result: &Result<T, ClientError>) {}
+
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn record_retry(_endpoint: &Url, _url: &Url) {}
@@ -0,0 +1,163 @@
+//! Tests of the code paths that rely on unsafe code, meant to surface undefined behaviour
+//! when run under Miri:
+//!
+//! ```sh
+//! cargo +nightly miri test --test miri
+//! ```
+//!
+//! They also run as part of the normal test suite. The allocation and retyping code of
+//! `simple_dst` itself is tested in that crate, including the paths that this crate doesn't
+//! reach: zero-sized types and initialisation that fails.
+
+use std::{rc::Rc, sync::Arc};
+
+use hamsando::domain::{Domain, Root, WireName};
+
+#[test]
+fn allocation() {
+    let domain = Domain::parse::<Box<_>>("www.example.com").unwrap();
+    assert_eq!(domain.as_str(), "www.example.com");
+    let root = Root::parse::<Box<_>>("example.com").unwrap();
+    assert_eq!(root.as_str(), "example.com");
+
+    let rc = Domain::parse::<Rc<_>>("www.example.com").unwrap();
+    assert_eq!(*rc, *domain);
+    let arc = Root::parse::<Arc<_>>("example.com").unwrap();
+    assert_eq!(*arc, *root);
+
+    // The longest domain allowed, to exercise a larger allocation.
+    let label = "a".repeat(63);
+    let long = format!("{}.{label}.{label}.{label}.example.com", "b".repeat(49));
+    assert_eq!(long.len(), 253);
+    let domain = Domain::parse::<Box<_>>(&long).unwrap();
+    assert_eq!(domain.root_str(), "example.com");
+}
+
+#[test]
+fn unchecked_allocation() {
+    // SAFETY: the indices are those of the separators before the root and the suffix.
+    let domain =
+        unsafe { Domain::new_unchecked::<Box<_>>(Some(3), 11, false, "www.bücher.com") }.unwrap();
+    assert_eq!(domain.prefix(), Some("www"));
+    assert_eq!(domain.root_str(), "bücher.com");
+    assert_eq!(domain.suffix(), "com");
+}
+
+#[test]
+fn smallest_allocation() {
+    // SAFETY: only the string is read, which doesn't depend on the indices.
+    let empty = unsafe { Domain::new_unchecked::<Box<_>>(None, 0, false, "") }.unwrap();
+    assert_eq!(empty.as_str(), "");
+    let cloned: Box<Domain> = empty.to_owned();
+    drop(empty);
+    assert_eq!(cloned.as_str(), "");
+
+    // Just the root label, without any label offsets before it.
+    let root = WireName::parse::<Rc<_>>(b"\x00").unwrap();
+    assert_eq!(root.label_count(), 0);
+    assert_eq!(root.as_bytes(), &b"\x00"[..]);
+    assert_eq!(root.labels().count(), 0);
+    let cloned: Box<WireName> = (*root).to_owned();
+    drop(root);
+    assert_eq!(cloned.as_bytes(), &b"\x00"[..]);
+}
+
+#[test]
+fn header_alignment() {
+    fn assert_aligned<T: ?Sized>(ptr: *const T) {
+        assert_eq!(ptr.cast::<u8>().addr() % align_of::<usize>(), 0);
+    }
+
+    // The headers contain usizes, so they are more aligned than the bytes of the tails,
+    // also when they follow the reference counts of an Rc or Arc.
+    let boxed = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    assert_aligned(&raw const *boxed);
+    let rc = Domain::parse::<Rc<_>>("www.example.com.").unwrap();
+    assert_aligned(&raw const *rc);
+    assert_eq!(rc.root_str(), "example.com.");
+    assert!(rc.was_fqdn());
+    let arc = Root::parse::<Arc<_>>("example.com.").unwrap();
+    assert_aligned(&raw const *arc);
+    assert_eq!(arc.suffix(), "com");
+
+    let name = WireName::parse::<Arc<_>>(b"\x03www\x07example\x03com\x00").unwrap();
+    assert_aligned(&raw const *name);
+    assert_eq!(name.label(2), Some(&b"com"[..]));
+}
+
+#[test]
+fn cloning() {
+    let domain = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    let cloned: Box<Domain> = domain.to_owned();
+    drop(domain);
+    assert_eq!(cloned.as_str(), "www.example.com.");
+
+    let root: Box<Root> = cloned.root().to_owned();
+    drop(cloned);
+    assert_eq!(root.as_str(), "example.com.");
+
+    let name = WireName::parse::<Box<_>>(b"\x03www\x07example\x03com\x00").unwrap();
+    let cloned: Box<WireName> = name.to_owned();
+    drop(name);
+    assert_eq!(cloned.label_count(), 3);
+    assert_eq!(
+        cloned.labels().collect::<Vec<_>>(),
+        [&b"www"[..], b"example", b"com"]
+    );
+}
+
+#[test]
+fn root_cast() {
+    let domain = Domain::parse::<Box<_>>("www.example.co.uk.").unwrap();
+    let root = domain.root();
+    assert_eq!(root.as_str(), "example.co.uk.");
+    assert_eq!(root.suffix(), "co.uk");
+    assert!(root.was_fqdn());
+}
+
+#[test]
+fn not_fqdn_reslice() {
+    let domain = Domain::parse::<Box<_>>("www.example.com.").unwrap();
+    let relative = domain.not_fqdn();
+    assert_eq!(relative.as_str(), "www.example.com");
+    assert_eq!(relative.prefix(), Some("www"));
+    assert_eq!(relative.suffix(), "com");
+    assert_eq!(relative.root().not_fqdn().as_str(), "example.com");
+    let owned: Box<Domain> = relative.to_owned();
+    assert_eq!(owned.as_str(), "www.example.com");
+
+    let root = Root::parse::<Box<_>>("example.com.").unwrap();
+    let relative = root.not_fqdn();
+    assert_eq!(relative.as_str(), "example.com");
+    assert_eq!(relative.suffix(), "com");
+    let owned: Box<Root> = relative.to_owned();
+    assert_eq!(owned.as_str(), "example.com");
+}
+
+#[test]
+fn domain_into_root() {
+    // Without a prefix, the allocation is reused.
+    let domain = Domain::parse::<Box<_>>("example.com.").unwrap();
+    let root: Box<Root> = domain.into();
+    assert_eq!(root.as_str(), "example.com.");
+    assert_eq!(root.suffix(), "com");
+
+    let domain = Domain::parse::<Box<_>>("www.example.com").unwrap();
+    let root: Box<Root> = domain.into();
+    assert_eq!(root.as_str(), "example.com");
+    assert_eq!(root.suffix(), "com");
+}
+
+#[test]
+fn label_manipulation() {
+    let domain = Domain::parse::<Box<_>>("a.b.example.com").unwrap();
+    let stripped = domain.strip_leftmost_label().unwrap();
+    drop(domain);
+    assert_eq!(stripped.as_str(), "b.example.com");
+    let stripped = stripped.strip_leftmost_label().unwrap();
+    assert_eq!(stripped.as_str(), "example.com");
+
+    let prepended = stripped.with_subdomain("www").unwrap();
+    drop(stripped);
+    assert_eq!(prepended.as_str(), "www.example.com");
+}